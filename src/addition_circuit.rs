@@ -17,7 +17,9 @@ pub struct AdditionCircuit<F: PrimeField> {
 
 // Constructor for DummyCircuit
 impl<F: PrimeField> BenchCircuit<F> for AdditionCircuit<F> {
-    fn new_random<R: RngCore>(rng: &mut R, rounds: usize) -> Self {
+    type Params = ();
+
+    fn new_random<R: RngCore>(_params: &(), rng: &mut R, rounds: usize) -> Self {
         AdditionCircuit { 
             a: <F>::rand(rng), 
             b: <F>::rand(rng), 
@@ -26,8 +28,8 @@ impl<F: PrimeField> BenchCircuit<F> for AdditionCircuit<F> {
         }
     }
 
-    fn get_result(&self) -> F {
-        (self.a + self.s) * self.b
+    fn public_inputs(&self) -> Vec<F> {
+        vec![(self.a + self.s) * self.b]
     }
 } 
 
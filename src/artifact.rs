@@ -0,0 +1,69 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use blake2::{Blake2s, Digest};
+
+// Magic bytes at the start of each kind of file
+pub const SRS: &[u8; 8] = b"TFMSRS02";
+pub const PROVING_KEY: &[u8; 8] = b"TFMPK001";
+pub const VERIFYING_KEY: &[u8; 8] = b"TFMVK001";
+pub const PROOF: &[u8; 8] = b"TFMPRF01";
+pub const PUBLIC_INPUTS: &[u8; 8] = b"TFMPUB01";
+
+/// Writes `value` preceded by a header with the kind of file, the curve and a
+/// digest of the serialized value.
+pub fn write<T: CanonicalSerialize>(path: &Path, magic: &[u8; 8], curve: &str, value: &T) -> std::io::Result<()> {
+    let mut body = Vec::new();
+    value.serialize_uncompressed(&mut body).unwrap();
+
+    let mut file = fs::File::create(path)?;
+    file.write_all(magic)?;
+    file.write_all(&(curve.len() as u8).to_le_bytes())?;
+    file.write_all(curve.as_bytes())?;
+    file.write_all(&Blake2s::digest(&body))?;
+    file.write_all(&body)
+}
+
+// Checks the header of a file written by `write` and returns its body
+fn read_body(path: &Path, magic: &[u8; 8], curve: &str) -> Result<Vec<u8>, String> {
+    let mut content = Vec::new();
+    fs::File::open(path)
+        .and_then(|mut file| file.read_to_end(&mut content))
+        .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+
+    let invalid = || format!("{} is not a valid {} file", path.display(), String::from_utf8_lossy(magic));
+    let (file_magic, rest) = content.split_at_checked(magic.len()).ok_or_else(invalid)?;
+    if file_magic != magic {
+        return Err(invalid());
+    }
+    let (curve_len, rest) = rest.split_first().ok_or_else(invalid)?;
+    let (file_curve, rest) = rest.split_at_checked(*curve_len as usize).ok_or_else(invalid)?;
+    if file_curve != curve.as_bytes() {
+        return Err(format!(
+            "{} belongs to curve {}, not {}",
+            path.display(),
+            String::from_utf8_lossy(file_curve),
+            curve
+        ));
+    }
+    let (digest, body) = rest.split_at_checked(32).ok_or_else(invalid)?;
+    if Blake2s::digest(body).as_slice() != digest {
+        return Err(format!("{} is corrupted, its digest does not match", path.display()));
+    }
+    Ok(body.to_vec())
+}
+
+/// Reads a file written by `write`, checking that every point is valid
+pub fn read<T: CanonicalDeserialize>(path: &Path, magic: &[u8; 8], curve: &str) -> Result<T, String> {
+    let body = read_body(path, magic, curve)?;
+    T::deserialize_uncompressed(&body[..]).map_err(|e| format!("cannot deserialize {}: {}", path.display(), e))
+}
+
+/// Reads a file written by `write` without checking the points. The digest only
+/// protects against corruption, so this is meant for files we generated.
+pub fn read_unchecked<T: CanonicalDeserialize>(path: &Path, magic: &[u8; 8], curve: &str) -> Result<T, String> {
+    let body = read_body(path, magic, curve)?;
+    T::deserialize_unchecked(&body[..]).map_err(|e| format!("cannot deserialize {}: {}", path.display(), e))
+}
@@ -0,0 +1,315 @@
+//! Verification of several Marlin proofs of the same index with a single
+//! KZG pairing check.
+//!
+//! Each proof still runs its own AHP verifier, but instead of checking its
+//! KZG openings with one product of pairings, the openings of every proof are
+//! combined with random coefficients and checked together by
+//! `KZG10::batch_check`. The combination of the commitments follows
+//! `check_combinations` of `MarlinKZG10` in ark-poly-commit 0.3.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Instant;
+
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{One, Zero};
+use ark_marlin::{Error, IndexProverKey, IndexVerifierKey};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly_commit::kzg10::{self, KZG10};
+use ark_poly_commit::marlin_pc;
+use ark_std::rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::bench::{MarlinProof, PC};
+use crate::circuit_traits::BenchCircuit;
+use crate::instrumented::{InstrumentedMarlin, PreparedCheck, ProverTimings, VerifierTimings};
+use crate::public_inputs::WithPublicInputs;
+use crate::seed::{self, Stream};
+use crate::soundness;
+use crate::stats::format_ns;
+use crate::transcript::Transcript;
+use crate::RunConfig;
+
+type KZGError = ark_poly_commit::Error;
+
+// Commitment to a linear combination, with its degree bound
+struct CombinedCommitment<E: PairingEngine> {
+    comm: E::G1Projective,
+    shifted_comm: Option<E::G1Projective>,
+    degree_bound: Option<usize>,
+}
+
+// KZG openings that a single proof needs to check
+struct Openings<E: PairingEngine> {
+    commitments: Vec<kzg10::Commitment<E>>,
+    points: Vec<E::Fr>,
+    values: Vec<E::Fr>,
+}
+
+fn scaled<E: PairingEngine>(mut point: E::G1Projective, scalar: E::Fr) -> E::G1Projective {
+    point *= scalar;
+    point
+}
+
+// Reduces the prepared check of one proof to one KZG opening per query point
+fn openings<E: PairingEngine>(
+    vk: &marlin_pc::VerifierKey<E>,
+    check: PreparedCheck<E::Fr, PC<E>>,
+) -> Result<Openings<E>, KZGError> {
+    let comms: BTreeMap<_, _> = check.commitments.iter().map(|c| (c.label().clone(), c)).collect();
+    let mut evaluations = check.evaluations;
+
+    // Commitments of the linear combinations
+    let mut lc_comms = BTreeMap::new();
+    for lc in &check.lc_s {
+        let mut combined = CombinedCommitment::<E> {
+            comm: E::G1Projective::zero(),
+            shifted_comm: None,
+            degree_bound: None,
+        };
+        for (coeff, term) in &lc.terms {
+            match term {
+                ark_poly_commit::LCTerm::One => {
+                    for ((label, _), eval) in evaluations.iter_mut() {
+                        if label == &lc.label {
+                            *eval -= coeff;
+                        }
+                    }
+                }
+                ark_poly_commit::LCTerm::PolyLabel(label) => {
+                    let comm = comms
+                        .get(label)
+                        .ok_or_else(|| KZGError::MissingPolynomial { label: label.clone() })?;
+                    if let Some(degree_bound) = comm.degree_bound() {
+                        if lc.terms.len() != 1 {
+                            return Err(KZGError::EquationHasDegreeBounds(lc.label.clone()));
+                        }
+                        combined.degree_bound = Some(degree_bound);
+                    }
+                    let comm = comm.commitment();
+                    combined.comm += &comm.comm.0.mul(*coeff);
+                    if let Some(shifted) = &comm.shifted_comm {
+                        let shifted = shifted.0.mul(*coeff);
+                        combined.shifted_comm = Some(combined.shifted_comm.unwrap_or_else(Zero::zero) + shifted);
+                    }
+                }
+            }
+        }
+        lc_comms.insert(lc.label.clone(), combined);
+    }
+
+    // Labels queried at each point
+    let mut queries: BTreeMap<&String, (E::Fr, BTreeSet<&String>)> = BTreeMap::new();
+    for (label, (point_label, point)) in &check.query_set {
+        queries.entry(point_label).or_insert((*point, BTreeSet::new())).1.insert(label);
+    }
+
+    // Combine the commitments of each point with powers of the opening challenge
+    let mut openings = Openings {
+        commitments: Vec::new(),
+        points: Vec::new(),
+        values: Vec::new(),
+    };
+    for (point, labels) in queries.into_values() {
+        let mut comm = E::G1Projective::zero();
+        let mut value = E::Fr::zero();
+        let mut challenge = E::Fr::one();
+        for label in labels {
+            let lc = lc_comms
+                .get(label)
+                .ok_or_else(|| KZGError::MissingPolynomial { label: label.clone() })?;
+            let eval = *evaluations
+                .get(&(label.clone(), point))
+                .ok_or_else(|| KZGError::MissingEvaluation { label: label.clone() })?;
+
+            comm += &scaled::<E>(lc.comm, challenge);
+            value += eval * challenge;
+            challenge *= check.opening_challenge;
+
+            if let Some(degree_bound) = lc.degree_bound {
+                let shift_power = vk
+                    .get_shift_power(degree_bound)
+                    .ok_or(KZGError::UnsupportedDegreeBound(degree_bound))?;
+                let shifted = lc.shifted_comm.ok_or(KZGError::MissingPolynomial { label: label.clone() })?;
+                let adjusted = shifted - shift_power.mul(eval);
+                comm += &scaled::<E>(adjusted, challenge);
+                challenge *= check.opening_challenge;
+            }
+        }
+        openings.commitments.push(kzg10::Commitment(comm.into_affine()));
+        openings.points.push(point);
+        openings.values.push(value);
+    }
+    Ok(openings)
+}
+
+/// Verifies several proofs of the same index together, accepting only if all
+/// of them are valid
+pub fn batch_verify<E: PairingEngine, FS: Transcript, R: RngCore>(
+    index_vk: &IndexVerifierKey<E::Fr, PC<E>>,
+    instances: &[(Vec<E::Fr>, &MarlinProof<E>)],
+    rng: &mut R,
+) -> Result<bool, Error<KZGError>> {
+    let mut commitments = Vec::new();
+    let mut points = Vec::new();
+    let mut values = Vec::new();
+    let mut proofs = Vec::new();
+
+    for (public_input, proof) in instances {
+        let check = InstrumentedMarlin::<E::Fr, PC<E>, FS>::prepare_check(index_vk, public_input, proof)?;
+        let openings = openings(&index_vk.verifier_key, check).map_err(Error::from_pc_err)?;
+        if openings.points.len() != proof.pc_proof.proof.len() {
+            return Ok(false);
+        }
+        commitments.extend(openings.commitments);
+        points.extend(openings.points);
+        values.extend(openings.values);
+        proofs.extend(proof.pc_proof.proof.iter().cloned());
+    }
+
+    KZG10::<E, DensePolynomial<E::Fr>>::batch_check(
+        &index_vk.verifier_key.vk,
+        &commitments,
+        &points,
+        &values,
+        &proofs,
+        rng,
+    )
+    .map_err(Error::from_pc_err)
+}
+
+/// Time to verify the same proofs one by one and as a batch
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatchVerification {
+    pub proofs: usize,
+    pub separate_ns: u64,
+    pub batched_ns: u64,
+    pub separate_per_proof_ns: f64,
+    pub batched_per_proof_ns: f64,
+    pub speedup: f64,
+    pub separate_verified: bool,
+    pub batched_verified: bool,
+    // The same batch with a wrong evaluation in its last proof
+    pub tampered_rejected: bool,
+}
+
+/// Proves `config.batch` instances of the circuit with different witnesses and
+/// verifies them with `Marlin::verify` one by one and with `batch_verify`.
+/// Circuits that ignore the RNG, like the fibonacci ones and circuit files,
+/// give the same statement every time, proved with different randomness.
+pub fn measure<E: PairingEngine, C: BenchCircuit<E::Fr>, FS: Transcript>(
+    config: &RunConfig,
+    params: &C::Params,
+    pk: &IndexProverKey<E::Fr, PC<E>>,
+    vk: &IndexVerifierKey<E::Fr, PC<E>>,
+) -> BatchVerification {
+    // The first instance is the benchmarked one, the rest continue its stream
+    let circuit_rng = &mut seed::rng(config.seed, Stream::Circuit);
+    let circuits: Vec<_> = (0..config.batch)
+        .map(|_| WithPublicInputs::new(C::new_random(params, circuit_rng, config.rounds), config.public_inputs, circuit_rng))
+        .collect();
+    let proofs: Vec<_> = circuits
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let prover_rng = &mut seed::prover_rng(config.seed, i as u64);
+            InstrumentedMarlin::<E::Fr, PC<E>, FS>::prove(pk, c.clone(), prover_rng, &mut ProverTimings::default()).unwrap()
+        })
+        .collect();
+    let mut instances: Vec<_> = circuits
+        .iter()
+        .zip(&proofs)
+        .map(|(c, proof)| (c.public_inputs(), proof))
+        .collect();
+
+    let verifier_rng = &mut seed::rng(config.seed, Stream::Verifier);
+    let start = Instant::now();
+    let separate_verified = instances
+        .iter()
+        .all(|(inputs, proof)| {
+            InstrumentedMarlin::<E::Fr, PC<E>, FS>::verify(vk, inputs, proof, verifier_rng, &mut VerifierTimings::default()).unwrap()
+        });
+    let separate_ns = start.elapsed().as_nanos() as u64;
+
+    let start = Instant::now();
+    let batched_verified = batch_verify::<E, FS, _>(vk, &instances, verifier_rng).unwrap();
+    let batched_ns = start.elapsed().as_nanos() as u64;
+
+    // The inputs may be empty, so the last proof is tampered instead
+    let last = instances.last().unwrap().1;
+    let wrong = soundness::tampered::<E::Fr, PC<E>>(last, |p| p.evaluations[0] += E::Fr::one());
+    instances.last_mut().unwrap().1 = &wrong;
+    let tampered_rejected = !batch_verify::<E, FS, _>(vk, &instances, verifier_rng).unwrap_or(false);
+
+    BatchVerification {
+        proofs: config.batch,
+        separate_ns,
+        batched_ns,
+        separate_per_proof_ns: separate_ns as f64 / config.batch as f64,
+        batched_per_proof_ns: batched_ns as f64 / config.batch as f64,
+        speedup: separate_ns as f64 / batched_ns as f64,
+        separate_verified,
+        batched_verified,
+        tampered_rejected,
+    }
+}
+
+impl BatchVerification {
+    /// Whether the honest proofs were accepted both ways and the tampered batch rejected
+    pub fn passed(&self) -> bool {
+        self.separate_verified && self.batched_verified && self.tampered_rejected
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} proofs, separate {} ({} per proof), batched {} ({} per proof), speedup x{:.2}",
+            self.proofs,
+            format_ns(self.separate_ns as f64),
+            format_ns(self.separate_per_proof_ns),
+            format_ns(self.batched_ns as f64),
+            format_ns(self.batched_per_proof_ns),
+            self.speedup
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_ff::One;
+    use ark_marlin::IndexVerifierKey;
+
+    use super::{batch_verify, PC};
+    use crate::bench::{self, MarlinProof};
+    use crate::seed::{self, Stream};
+    use crate::soundness;
+    use crate::transcript::Blake2sTranscript;
+
+    fn verify(vk: &IndexVerifierKey<Fr, PC<Bls12_381>>, instances: &[(Vec<Fr>, &MarlinProof<Bls12_381>)]) -> bool {
+        batch_verify::<Bls12_381, Blake2sTranscript, _>(vk, instances, &mut seed::rng(0, Stream::Verifier))
+            .unwrap_or(false)
+    }
+
+    #[test]
+    fn honest_proofs_are_accepted() {
+        let (vk, instances) = bench::proved::<Fr, PC<Bls12_381>, Blake2sTranscript>(3);
+        let batch: Vec<_> = instances.iter().map(|(inputs, proof)| (inputs.clone(), proof)).collect();
+        assert!(verify(&vk, &batch));
+    }
+
+    #[test]
+    fn tampered_proof_is_rejected() {
+        let (vk, instances) = bench::proved::<Fr, PC<Bls12_381>, Blake2sTranscript>(3);
+        let wrong = soundness::tampered::<Fr, PC<Bls12_381>>(&instances[1].1, |p| p.evaluations[0] += Fr::one());
+        let mut batch: Vec<_> = instances.iter().map(|(inputs, proof)| (inputs.clone(), proof)).collect();
+        batch[1].1 = &wrong;
+        assert!(!verify(&vk, &batch));
+    }
+
+    #[test]
+    fn tampered_public_input_is_rejected() {
+        let (vk, instances) = bench::proved::<Fr, PC<Bls12_381>, Blake2sTranscript>(3);
+        let mut batch: Vec<_> = instances.iter().map(|(inputs, proof)| (inputs.clone(), proof)).collect();
+        batch[1].0[0] += Fr::one();
+        assert!(!verify(&vk, &batch));
+    }
+}
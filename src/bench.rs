@@ -0,0 +1,228 @@
+use ark_ec::PairingEngine;
+use ark_ff::PrimeField;
+use ark_marlin::{Marlin, Proof};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly_commit::marlin_pc::MarlinKZG10;
+use ark_poly_commit::PCUniversalParams;
+use ark_relations::r1cs::{ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, OptimizationGoal};
+use blake2::Blake2s;
+
+use crate::circuit_traits::BenchCircuit;
+use crate::constraint_stats::ConstraintStats;
+use crate::fingerprint::{self, Fingerprints};
+use crate::instrumented::{
+    IndexerPhases, IndexerTimings, InstrumentedMarlin, ProverPhases, ProverTimings, VerifierPhases,
+    VerifierTimings,
+};
+use crate::memory::{self, MemoryPhases, MemoryUsage};
+use crate::pc::BenchPC;
+use crate::public_inputs::WithPublicInputs;
+use crate::report::{Backend, RunResult};
+use crate::sizes::ArtifactSizes;
+use crate::stats::PhaseStats;
+use crate::seed::{self, Stream};
+use crate::soundness;
+use crate::srs;
+use crate::threads;
+use crate::transcript::Transcript;
+use crate::print_panic;
+use crate::RunConfig;
+
+pub type PC<E> = MarlinKZG10<E, DensePolynomial<<E as PairingEngine>::Fr>>;
+
+// Marlin of ark-marlin itself, without the timings of `InstrumentedMarlin`
+pub type UpstreamMarlin<E> = Marlin<<E as PairingEngine>::Fr, PC<E>, Blake2s>;
+
+pub type MarlinProof<E> = Proof<<E as PairingEngine>::Fr, PC<E>>;
+
+/// Generates the constraint system of the circuit without optimizations and
+/// its matrices, measuring the memory used
+pub fn synthesize<F: PrimeField, C: ConstraintSynthesizer<F> + Clone>(c: &C) -> (ConstraintMatrices<F>, MemoryUsage) {
+    let cs = ConstraintSystem::<F>::new_ref();
+    cs.set_optimization_goal(OptimizationGoal::None);
+
+    memory::measure(|| {
+        c.clone()
+            .generate_constraints(cs.clone())
+            .unwrap_or_else(|e| print_panic!("Cannot synthesize the circuit: {:?}", e));
+        // Inlines the symbolic linear combinations, which the matrices cannot hold
+        cs.finalize();
+        cs.to_matrices().unwrap()
+    })
+}
+
+/// Runs the whole Marlin pipeline for the circuit `C` over the field `F` with
+/// the commitment scheme `P` and the Fiat-Shamir transcript `FS`
+pub fn bench<F: PrimeField, C: BenchCircuit<F>, P: BenchPC<F>, FS: Transcript>(
+    config: &RunConfig,
+    params: &C::Params,
+) -> RunResult {
+    let circuit_rng = &mut seed::rng(config.seed, Stream::Circuit);
+    let c = WithPublicInputs::new(C::new_random(params, circuit_rng, config.rounds), config.public_inputs, circuit_rng);
+    let inputs = c.public_inputs();
+    let prover_rng = &mut seed::prover_rng(config.seed, config.prover_variant);
+    let verifier_rng = &mut seed::rng(config.seed, Stream::Verifier);
+
+    let mut memory = MemoryPhases::default();
+
+    let (matrices, synthesis_memory) = synthesize(&c);
+    memory.synthesis = synthesis_memory;
+    let constraint_system = ConstraintStats::new(&matrices);
+
+    // Size the SRS from the matrices once Marlin pads them
+    let max_degree = srs::required_degree(&matrices);
+
+    // Load or generate the SRS
+    let ((srs, srs_cached, setup_time), setup_memory) = memory::measure(|| {
+        let start = std::time::Instant::now();
+        let (srs, cached) = srs::load_or_setup::<_, P>(
+            config.srs_dir.as_deref(),
+            &P::SCHEME.srs_name(&config.curve),
+            config.seed,
+            max_degree,
+            config.srs_max_degree,
+        );
+        (srs, cached, start.elapsed())
+    });
+    memory.setup = setup_memory;
+    // The results do not depend on how big the stored SRS is
+    let srs = P::truncate(&srs, max_degree);
+    let setup_sample = PhaseStats::from_samples(vec![setup_time.as_nanos() as u64]);
+    let (setup, srs_load) = match srs_cached {
+        true => (PhaseStats::default(), setup_sample),
+        false => (setup_sample, PhaseStats::default()),
+    };
+
+    let mut indexer_samples = Vec::new();
+    let mut prover_samples = Vec::new();
+    let mut verifier_samples = Vec::new();
+    let mut indexer_timings = Vec::new();
+    let mut prover_timings = Vec::new();
+    let mut verifier_timings = Vec::new();
+    let mut verified = true;
+    let mut artifacts = None;
+
+    for i in 0..(config.warmup + config.iterations) {
+        // Generate the setup
+        let mut indexer_phases = IndexerTimings::default();
+        let ((pk, vk, indexer_time), index_memory) = memory::measure(|| {
+            let start = std::time::Instant::now();
+            let (pk, vk) = InstrumentedMarlin::<F, P, FS>::index(&srs, c.clone(), &mut indexer_phases).unwrap();
+            (pk, vk, start.elapsed())
+        });
+
+        // Generate the proof
+        let mut prover_phases = ProverTimings::default();
+        let ((proof, prover_time), prove_memory) = memory::measure(|| {
+            let start = std::time::Instant::now();
+            let proof = InstrumentedMarlin::<F, P, FS>::prove(&pk, c.clone(), prover_rng, &mut prover_phases).unwrap();
+            (proof, start.elapsed())
+        });
+
+        // Check the proof
+        let mut verifier_phases = VerifierTimings::default();
+        let ((res, verifier_time), verify_memory) = memory::measure(|| {
+            let start = std::time::Instant::now();
+            let res = InstrumentedMarlin::<F, P, FS>::verify(&vk, &inputs, &proof, verifier_rng, &mut verifier_phases)
+                .unwrap();
+            (res, start.elapsed())
+        });
+        verified &= res;
+
+        memory.index = index_memory;
+        memory.prove = prove_memory;
+        memory.verify = verify_memory;
+
+        if i >= config.warmup {
+            indexer_samples.push(indexer_time.as_nanos() as u64);
+            prover_samples.push(prover_time.as_nanos() as u64);
+            verifier_samples.push(verifier_time.as_nanos() as u64);
+            indexer_timings.push(indexer_phases);
+            prover_timings.push(prover_phases);
+            verifier_timings.push(verifier_phases);
+        }
+        artifacts = Some((pk, vk, proof));
+    }
+
+    // The sizes don't change between iterations, so the last ones are measured
+    let (pk, vk, proof) = artifacts.unwrap();
+    let sizes = ArtifactSizes::measure(&srs, &pk, &vk, &proof);
+    let fingerprints = Fingerprints {
+        matrices: fingerprint::of_matrices(&matrices),
+        srs: fingerprint::of(&srs),
+        verifying_key: fingerprint::of(&vk),
+        proof: fingerprint::of(&proof),
+    };
+
+    let soundness = if config.soundness_check {
+        soundness::check::<F, C, P, FS>(config, params, &srs, &vk, &inputs, &proof)
+    } else {
+        Vec::new()
+    };
+
+    let batch = if config.batch > 0 { P::batch::<C, FS>(config, params, &pk, &vk) } else { None };
+
+    RunResult {
+        backend: Backend::Marlin,
+        pc: Some(P::SCHEME),
+        fs: Some(config.fs),
+        circuit: config.circuit.clone(),
+        curve: config.curve.clone(),
+        rounds: config.rounds,
+        public_inputs: config.public_inputs,
+        seed: config.seed,
+        threads: threads::current(),
+        warmup: config.warmup,
+        srs_max_degree: srs.max_degree(),
+        srs_cached,
+        iterations: config.iterations,
+        constraint_system,
+        indexer: PhaseStats::from_samples(indexer_samples),
+        prover: PhaseStats::from_samples(prover_samples),
+        verifier: PhaseStats::from_samples(verifier_samples),
+        setup,
+        srs_load,
+        indexer_phases: IndexerPhases::from_timings(&indexer_timings),
+        prover_phases: ProverPhases::from_timings(&prover_timings),
+        verifier_phases: VerifierPhases::from_timings(&verifier_timings),
+        sizes,
+        memory: config.memory.then_some(memory),
+        fingerprints,
+        soundness,
+        batch,
+        verified,
+    }
+}
+
+/// Public inputs of an instance and its proof
+#[cfg(test)]
+pub type ProvedInstance<F, P> = (Vec<F>, Proof<F, P>);
+
+/// Verifying key of a basic circuit with 4 rounds and proofs of `count`
+/// instances of it with different public inputs, made with the commitment
+/// scheme `P` and the transcript `FS`
+#[cfg(test)]
+pub fn proved<F: PrimeField, P: BenchPC<F>, FS: Transcript>(
+    count: usize,
+) -> (ark_marlin::IndexVerifierKey<F, P>, Vec<ProvedInstance<F, P>>) {
+    use crate::basic_circuit::BasicCircuit;
+
+    let rng = &mut seed::rng(0, Stream::Circuit);
+    let circuits: Vec<_> = (0..count).map(|_| BasicCircuit::<F>::new_random(&(), rng, 4)).collect();
+
+    let (matrices, _) = synthesize(&circuits[0]);
+    let srs = P::setup(srs::required_degree(&matrices), None, &mut seed::rng(0, Stream::Setup)).unwrap();
+    let (pk, vk) = InstrumentedMarlin::<F, P, FS>::index(&srs, circuits[0], &mut IndexerTimings::default()).unwrap();
+
+    let instances = circuits
+        .into_iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let inputs = c.public_inputs();
+            let prover_rng = &mut seed::prover_rng(0, i as u64);
+            let proof = InstrumentedMarlin::<F, P, FS>::prove(&pk, c, prover_rng, &mut ProverTimings::default()).unwrap();
+            (inputs, proof)
+        })
+        .collect();
+    (vk, instances)
+}
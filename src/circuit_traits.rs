@@ -1,7 +1,14 @@
 use ark_ff::PrimeField;
+use ark_relations::r1cs::ConstraintSynthesizer;
 use ark_std::rand::RngCore;
 
-pub trait BenchCircuit<F: PrimeField> {
-    fn new_random<R: RngCore>(rng: &mut R, rounds: usize) -> Self;
-    fn get_result(&self) -> F;
+pub trait BenchCircuit<F: PrimeField>: ConstraintSynthesizer<F> + Clone {
+    /// What the circuit is built from besides the RNG and the rounds, like the
+    /// contents of a circuit file
+    type Params;
+
+    fn new_random<R: RngCore>(params: &Self::Params, rng: &mut R, rounds: usize) -> Self;
+    /// Values of the instance variables after the constant one, in the order
+    /// the circuit allocates them
+    fn public_inputs(&self) -> Vec<F>;
 }
\ No newline at end of file
@@ -0,0 +1,189 @@
+//! Size and shape of the constraint system of a circuit, from its matrices and
+//! the padding Marlin adds to them when it indexes the circuit.
+
+use ark_ff::PrimeField;
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_relations::r1cs::ConstraintMatrices;
+use serde::{Deserialize, Serialize};
+
+use crate::print_panic;
+
+/// Non-zero entries of one of the R1CS matrices
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MatrixStats {
+    pub non_zero: usize,
+    // Percentage of non-zero entries among constraints x variables
+    pub density: f64,
+}
+
+/// Evaluation domain of Marlin, a power of two at least as big as the
+/// elements it has to hold
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DomainStats {
+    pub used: usize,
+    pub size: usize,
+    // Percentage of the domain that is padding
+    pub waste: f64,
+}
+
+impl DomainStats {
+    fn new<F: PrimeField>(used: usize, elements: usize) -> Self {
+        let size = GeneralEvaluationDomain::<F>::new(elements)
+            .unwrap_or_else(|| print_panic!("There is no domain of {} elements in this field", elements))
+            .size();
+        DomainStats {
+            used,
+            size,
+            waste: (size - used) as f64 * 100.0 / size as f64,
+        }
+    }
+}
+
+/// Statistics of a constraint system, comparable across circuits
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ConstraintStats {
+    pub constraints: usize,
+    // Includes the constant one
+    pub instance_variables: usize,
+    pub witness_variables: usize,
+    pub variables: usize,
+    pub a: MatrixStats,
+    pub b: MatrixStats,
+    pub c: MatrixStats,
+    // Domains of Marlin, none for Groth16
+    // Constraints and variables, once the matrices are made square
+    #[serde(default)]
+    pub domain_h: Option<DomainStats>,
+    // Non-zero entries of the densest matrix, once A and B are balanced
+    #[serde(default)]
+    pub domain_k: Option<DomainStats>,
+    // Public inputs, padded to a power of two by Marlin
+    #[serde(default)]
+    pub domain_x: Option<DomainStats>,
+}
+
+impl ConstraintStats {
+    /// Statistics of the constraint system with these matrices, without the
+    /// domains of Marlin
+    pub fn of_matrices<F: PrimeField>(matrices: &ConstraintMatrices<F>) -> Self {
+        let variables = matrices.num_instance_variables + matrices.num_witness_variables;
+        // An empty matrix has no entries, so it is not dense at all instead of NaN,
+        // which JSON cannot hold
+        let entries = matrices.num_constraints * variables;
+        let matrix = |non_zero: usize| MatrixStats {
+            non_zero,
+            density: if entries == 0 { 0.0 } else { non_zero as f64 * 100.0 / entries as f64 },
+        };
+
+        ConstraintStats {
+            constraints: matrices.num_constraints,
+            instance_variables: matrices.num_instance_variables,
+            witness_variables: matrices.num_witness_variables,
+            variables,
+            a: matrix(matrices.a_num_non_zero),
+            b: matrix(matrices.b_num_non_zero),
+            c: matrix(matrices.c_num_non_zero),
+            domain_h: None,
+            domain_k: None,
+            domain_x: None,
+        }
+    }
+
+    /// Statistics of the constraint system with these matrices and the
+    /// domains Marlin indexes it with
+    pub fn new<F: PrimeField>(matrices: &ConstraintMatrices<F>) -> Self {
+        // Marlin pads the public inputs before making the matrices square, and
+        // balancing A and B never makes the densest matrix denser
+        let domain_x = DomainStats::new::<F>(matrices.num_instance_variables, matrices.num_instance_variables);
+        let square = matrices.num_constraints.max(domain_x.size + matrices.num_witness_variables);
+        let non_zero = matrices.a_num_non_zero.max(matrices.b_num_non_zero).max(matrices.c_num_non_zero);
+        let stats = Self::of_matrices(matrices);
+
+        ConstraintStats {
+            domain_h: Some(DomainStats::new::<F>(stats.variables.max(matrices.num_constraints), square)),
+            domain_k: Some(DomainStats::new::<F>(non_zero, non_zero)),
+            domain_x: Some(domain_x),
+            ..stats
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Fr;
+    use ark_marlin::ahp::AHPForR1CS;
+    use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+
+    use super::ConstraintStats;
+    use crate::bench::synthesize;
+    use crate::circuit_traits::BenchCircuit;
+    use crate::public_inputs::WithPublicInputs;
+    use crate::registry::{self, CircuitVisitor, CIRCUITS};
+    use crate::seed::{self, Stream};
+    use crate::srs;
+
+    fn domain_size(elements: usize) -> usize {
+        GeneralEvaluationDomain::<Fr>::new(elements).unwrap().size()
+    }
+
+    // The domains and the degree computed from the matrices must be the ones
+    // Marlin gets when it indexes the circuit
+    fn check<C: ConstraintSynthesizer<Fr> + Clone>(name: &str, circuit: C) {
+        let (matrices, _) = synthesize(&circuit);
+        let stats = ConstraintStats::new(&matrices);
+        let index = AHPForR1CS::<Fr>::index(circuit).unwrap();
+        let info = &index.index_info;
+
+        assert_eq!(stats.domain_h.unwrap().size, domain_size(info.num_constraints), "|H| of {}", name);
+        assert_eq!(stats.domain_k.unwrap().size, domain_size(info.num_non_zero), "|K| of {}", name);
+        assert_eq!(stats.domain_x.unwrap().size, domain_size(info.num_instance_variables), "|X| of {}", name);
+        assert_eq!(srs::required_degree(&matrices), index.max_degree(), "degree of {}", name);
+    }
+
+    struct CheckCircuit<'a>(&'a str);
+
+    impl CircuitVisitor<Fr> for CheckCircuit<'_> {
+        type Output = ();
+
+        fn visit<C: BenchCircuit<Fr>>(self, params: C::Params) {
+            let rng = &mut seed::rng(0, Stream::Circuit);
+            for rounds in [1, 3, 17, 100] {
+                check(&format!("{} with {} rounds", self.0, rounds), C::new_random(&params, rng, rounds));
+                for extra_inputs in [1, 5] {
+                    let circuit = WithPublicInputs::new(C::new_random(&params, rng, rounds), extra_inputs, rng);
+                    check(&format!("{} with {} rounds and {} extra inputs", self.0, rounds, extra_inputs), circuit);
+                }
+            }
+        }
+    }
+
+    #[derive(Clone)]
+    struct EmptyCircuit;
+
+    impl ConstraintSynthesizer<Fr> for EmptyCircuit {
+        fn generate_constraints(self, _cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            Ok(())
+        }
+    }
+
+    // The statistics of a circuit without constraints must survive a JSON round trip
+    #[test]
+    fn empty_circuit() {
+        let (matrices, _) = synthesize(&EmptyCircuit);
+        let stats = ConstraintStats::new(&matrices);
+        assert_eq!(stats.constraints, 0);
+        assert_eq!(stats.a.density, 0.0);
+
+        let json = serde_json::to_string(&stats).unwrap();
+        let read: ConstraintStats = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.a.density, 0.0);
+    }
+
+    #[test]
+    fn domains_match_the_index() {
+        for circuit in CIRCUITS {
+            registry::visit_circuit(circuit.name, CheckCircuit(circuit.name)).unwrap();
+        }
+    }
+}
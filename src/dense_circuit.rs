@@ -16,7 +16,9 @@ pub struct DenseCircuit<F: PrimeField> {
 
 // Constructor for DenseCircuit
 impl<F: PrimeField> BenchCircuit<F> for DenseCircuit<F> {
-    fn new_random<R: RngCore>(rng: &mut R, rounds: usize) -> Self {
+    type Params = ();
+
+    fn new_random<R: RngCore>(_params: &(), rng: &mut R, rounds: usize) -> Self {
         DenseCircuit { 
             a: <F>::rand(rng), 
             b: <F>::rand(rng), 
@@ -24,8 +26,8 @@ impl<F: PrimeField> BenchCircuit<F> for DenseCircuit<F> {
         }
     }
 
-    fn get_result(&self) -> F {
-        (self.a + self.b) * (self.a + self.b)
+    fn public_inputs(&self) -> Vec<F> {
+        vec![(self.a + self.b) * (self.a + self.b)]
     }
 } 
 
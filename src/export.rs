@@ -0,0 +1,316 @@
+//! Writes the constraint system of a circuit in the formats of circom, so that
+//! it can be inspected with snarkjs and proved by other implementations.
+//!
+//! The wires keep the order of arkworks, which is also the one of circom: the
+//! constant one, the public inputs and then the witness variables. Every
+//! public input is declared as an input, as the circuits do not tell outputs
+//! apart, and every witness variable as an internal wire.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ark_ec::PairingEngine;
+use ark_ff::{BigInteger, FpParameters, PrimeField};
+use ark_relations::r1cs::{ConstraintMatrices, ConstraintSynthesizer, ConstraintSystem, OptimizationGoal};
+use clap::Args as ClapArgs;
+use num_bigint::BigUint;
+use serde::Serialize;
+
+use crate::circuit_traits::BenchCircuit;
+use crate::registry::{self, CircuitVisitor, CurveVisitor};
+use crate::steps::{self, CircuitArgs};
+use crate::{print_info, print_panic};
+
+#[derive(ClapArgs, Debug)]
+pub struct ExportArgs {
+    #[command(flatten)]
+    circuit: CircuitArgs,
+
+    /// Where the matrices are written in the iden3 binary format
+    #[arg(long, default_value = "circuit.r1cs")]
+    r1cs: PathBuf,
+
+    /// Where the matrices are written as JSON, like `snarkjs r1cs export json`
+    #[arg(long, default_value = "circuit.json")]
+    json: PathBuf,
+
+    /// Where the assignment of every wire is written in the iden3 binary format
+    #[arg(long, default_value = "witness.wtns")]
+    wtns: PathBuf,
+}
+
+/// Matrices with the field names snarkjs uses
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct R1csJson {
+    n8: usize,
+    prime: String,
+    n_vars: usize,
+    n_outputs: usize,
+    n_pub_inputs: usize,
+    n_prv_inputs: usize,
+    n_labels: usize,
+    n_constraints: usize,
+    // A, B and C of each constraint, as coefficients by wire
+    constraints: Vec<[BTreeMap<usize, String>; 3]>,
+    // Label of each wire
+    map: Vec<usize>,
+}
+
+// Bytes of a field element, a whole number of 64-bit words as iden3 expects
+fn field_size<F: PrimeField>() -> usize {
+    F::Params::MODULUS.to_bytes_le().len()
+}
+
+fn push_u32(out: &mut Vec<u8>, value: usize) {
+    out.extend((value as u32).to_le_bytes());
+}
+
+// Elements are stored in little endian, outside of the Montgomery form
+fn push_field<F: PrimeField>(out: &mut Vec<u8>, value: &F) {
+    out.extend(value.into_repr().to_bytes_le());
+}
+
+// Header of the field shared by both formats
+fn push_field_header<F: PrimeField>(out: &mut Vec<u8>) {
+    push_u32(out, field_size::<F>());
+    out.extend(F::Params::MODULUS.to_bytes_le());
+}
+
+fn file(magic: &[u8; 4], version: u32, sections: &[(u32, Vec<u8>)]) -> Vec<u8> {
+    let mut out = magic.to_vec();
+    out.extend(version.to_le_bytes());
+    push_u32(&mut out, sections.len());
+    for (kind, data) in sections {
+        out.extend(kind.to_le_bytes());
+        out.extend((data.len() as u64).to_le_bytes());
+        out.extend(data);
+    }
+    out
+}
+
+fn num_wires<F: PrimeField>(matrices: &ConstraintMatrices<F>) -> usize {
+    matrices.num_instance_variables + matrices.num_witness_variables
+}
+
+fn rows<F: PrimeField>(matrices: &ConstraintMatrices<F>) -> impl Iterator<Item = [&Vec<(F, usize)>; 3]> {
+    matrices.a.iter().zip(&matrices.b).zip(&matrices.c).map(|((a, b), c)| [a, b, c])
+}
+
+fn r1cs<F: PrimeField>(matrices: &ConstraintMatrices<F>) -> Vec<u8> {
+    let wires = num_wires(matrices);
+
+    let mut header = Vec::new();
+    push_field_header::<F>(&mut header);
+    push_u32(&mut header, wires);
+    push_u32(&mut header, 0);
+    push_u32(&mut header, matrices.num_instance_variables - 1);
+    push_u32(&mut header, 0);
+    header.extend((wires as u64).to_le_bytes());
+    push_u32(&mut header, matrices.num_constraints);
+
+    let mut constraints = Vec::new();
+    for row in rows(matrices).flatten() {
+        push_u32(&mut constraints, row.len());
+        for (coeff, wire) in row {
+            push_u32(&mut constraints, *wire);
+            push_field(&mut constraints, coeff);
+        }
+    }
+
+    let labels = (0..wires as u64).flat_map(u64::to_le_bytes).collect();
+
+    file(b"r1cs", 1, &[(1, header), (2, constraints), (3, labels)])
+}
+
+fn wtns<F: PrimeField>(assignment: &[F]) -> Vec<u8> {
+    let mut header = Vec::new();
+    push_field_header::<F>(&mut header);
+    push_u32(&mut header, assignment.len());
+
+    let mut values = Vec::new();
+    for value in assignment {
+        push_field(&mut values, value);
+    }
+
+    file(b"wtns", 2, &[(1, header), (2, values)])
+}
+
+fn json<F: PrimeField>(matrices: &ConstraintMatrices<F>) -> R1csJson {
+    let wires = num_wires(matrices);
+    let decimal = |row: &Vec<(F, usize)>| {
+        row.iter()
+            .map(|(coeff, wire)| (*wire, Into::<BigUint>::into(*coeff).to_string()))
+            .collect()
+    };
+    R1csJson {
+        n8: field_size::<F>(),
+        prime: BigUint::from_bytes_le(&F::Params::MODULUS.to_bytes_le()).to_string(),
+        n_vars: wires,
+        n_outputs: 0,
+        n_pub_inputs: matrices.num_instance_variables - 1,
+        n_prv_inputs: 0,
+        n_labels: wires,
+        n_constraints: matrices.num_constraints,
+        constraints: rows(matrices).map(|row| row.map(decimal)).collect(),
+        map: (0..wires).collect(),
+    }
+}
+
+fn write(path: &Path, content: &[u8]) {
+    fs::write(path, content).unwrap_or_else(|e| print_panic!("Cannot write {}: {}", path.display(), e));
+    print_info!("Wrote {}", path.display());
+}
+
+// Matrices of the circuit and the assignment of every wire
+fn wires<F: PrimeField, C: ConstraintSynthesizer<F>>(c: C) -> (ConstraintMatrices<F>, Vec<F>) {
+    // Without optimizations, so that the wires are the variables of the circuit
+    let cs = ConstraintSystem::<F>::new_ref();
+    cs.set_optimization_goal(OptimizationGoal::None);
+    c.generate_constraints(cs.clone())
+        .unwrap_or_else(|e| print_panic!("Cannot synthesize the circuit: {:?}", e));
+    cs.finalize();
+    let matrices = cs.to_matrices().unwrap();
+    let cs = cs.borrow().unwrap();
+    let assignment = [cs.instance_assignment.as_slice(), cs.witness_assignment.as_slice()].concat();
+    (matrices, assignment)
+}
+
+fn export_circuit<E: PairingEngine, C: BenchCircuit<E::Fr>>(args: &ExportArgs, params: &C::Params) {
+    let (matrices, assignment) = wires(steps::circuit::<E, C>(&args.circuit, params));
+
+    print_info!(
+        "Constraints: {}, wires: {} ({} public inputs)",
+        matrices.num_constraints,
+        assignment.len(),
+        matrices.num_instance_variables - 1
+    );
+    write(&args.r1cs, &r1cs(&matrices));
+    write(&args.json, serde_json::to_string_pretty(&json(&matrices)).unwrap().as_bytes());
+    write(&args.wtns, &wtns(&assignment));
+}
+
+// Exports the circuit over each visited curve
+struct ExportOnCurve<'a>(&'a ExportArgs);
+
+// Exports each visited circuit over the pairing engine `E`
+struct ExportOnCircuit<'a, E>(&'a ExportArgs, std::marker::PhantomData<E>);
+
+impl CurveVisitor for ExportOnCurve<'_> {
+    type Output = Option<()>;
+
+    fn visit<E: PairingEngine>(self) -> Self::Output {
+        registry::visit_circuit(&self.0.circuit.system, ExportOnCircuit::<E>(self.0, Default::default()))
+    }
+}
+
+impl<E: PairingEngine> CircuitVisitor<E::Fr> for ExportOnCircuit<'_, E> {
+    type Output = ();
+
+    fn visit<C: BenchCircuit<E::Fr>>(self, params: C::Params) -> Self::Output {
+        export_circuit::<E, C>(self.0, &params)
+    }
+}
+
+/// Writes the matrices and the witness of the circuit
+pub fn export(args: &ExportArgs) {
+    registry::visit_curve(&args.circuit.curve, ExportOnCurve(args))
+        .flatten()
+        .unwrap_or_else(|| print_panic!("Invalid circuit {} or curve {}", args.circuit.system, args.circuit.curve))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use ark_bls12_381::Fr;
+    use ark_relations::r1cs::ConstraintMatrices;
+
+    use super::{r1cs, wires, wtns};
+    use crate::basic_circuit::BasicCircuit;
+    use crate::circuit_traits::BenchCircuit;
+    use crate::file_circuit::{self, FileCircuit};
+    use crate::public_inputs::WithPublicInputs;
+    use crate::seed::{self, Stream};
+
+    // Directory of the circuit files of a test, removed even if the test panics
+    struct Files(PathBuf);
+
+    impl Files {
+        fn write(name: &str, matrices: &ConstraintMatrices<Fr>, assignment: &[Fr]) -> Self {
+            let files = Files(std::env::temp_dir().join(format!("tfm-marlin-{}-{}", std::process::id(), name)));
+            fs::create_dir_all(&files.0).unwrap();
+            fs::write(files.r1cs(), r1cs(matrices)).unwrap();
+            fs::write(files.0.join("circuit.wtns"), wtns(assignment)).unwrap();
+            files
+        }
+
+        fn r1cs(&self) -> String {
+            self.0.join("circuit.r1cs").to_string_lossy().into_owned()
+        }
+    }
+
+    impl Drop for Files {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn exported() -> (ConstraintMatrices<Fr>, Vec<Fr>) {
+        let rng = &mut seed::rng(0, Stream::Circuit);
+        let circuit = WithPublicInputs::new(BasicCircuit::<Fr>::new_random(&(), rng, 4), 2, rng);
+        wires(circuit)
+    }
+
+    #[test]
+    fn round_trip() {
+        let (matrices, assignment) = exported();
+        let files = Files::write("round-trip", &matrices, &assignment);
+
+        let file = file_circuit::read(&files.r1cs());
+        let circuit = FileCircuit::<Fr>::new_random(&file, &mut seed::rng(0, Stream::Circuit), 1);
+        let (read, read_assignment) = wires(circuit);
+
+        assert_eq!(read.num_instance_variables, matrices.num_instance_variables);
+        assert_eq!(read.num_witness_variables, matrices.num_witness_variables);
+        assert_eq!(read.num_constraints, matrices.num_constraints);
+        assert_eq!(read.a, matrices.a);
+        assert_eq!(read.b, matrices.b);
+        assert_eq!(read.c, matrices.c);
+        assert_eq!(read_assignment, assignment);
+    }
+
+    #[test]
+    #[should_panic(expected = "is not the constant one")]
+    fn wire_zero_must_be_one() {
+        let (matrices, mut assignment) = exported();
+        assignment[0] = Fr::from(2u64);
+        let files = Files::write("wire-zero", &matrices, &assignment);
+        file_circuit::read(&files.r1cs());
+    }
+
+    #[test]
+    #[should_panic(expected = "is not smaller than the prime")]
+    fn values_must_be_canonical() {
+        let (matrices, assignment) = exported();
+        let files = Files::write("non-canonical", &matrices, &assignment);
+        // The last wire, whose value ends the file, becomes 2^256 - 1
+        let path = files.0.join("circuit.wtns");
+        let mut wtns = fs::read(&path).unwrap();
+        let len = wtns.len();
+        wtns[len - 32..].fill(0xff);
+        fs::write(&path, wtns).unwrap();
+        file_circuit::read(&files.r1cs());
+    }
+
+    #[test]
+    #[should_panic(expected = "does not assign")]
+    fn witness_must_assign_every_wire() {
+        let (matrices, mut assignment) = exported();
+        assignment.pop();
+        let files = Files::write("short-witness", &matrices, &assignment);
+        file_circuit::read(&files.r1cs());
+    }
+}
@@ -15,16 +15,18 @@ pub struct Fibonacci3Circuit<F> {
 
 // Constructor for Fibonacci3Circuit
 impl<F: PrimeField> BenchCircuit<F> for Fibonacci3Circuit<F> {
-    fn new_random<R: RngCore>(rng: &mut R, rounds: usize) -> Self {
+    type Params = ();
+
+    fn new_random<R: RngCore>(_params: &(), _rng: &mut R, rounds: usize) -> Self {
         Fibonacci3Circuit {   
-            x: <F>::from(0 as u32),
+            x: <F>::from(0u32),
             t: rounds + 3
         }
     }
 
-    fn get_result(&self) -> F {
-        let mut a = <F>::from(0 as u32);
-        let mut b = <F>::from(1 as u32);
+    fn public_inputs(&self) -> Vec<F> {
+        let mut a = self.x;
+        let mut b = <F>::from(1u32);
 
         for _ in 0..(self.t - 1) {
             let c = b;
@@ -32,7 +34,7 @@ impl<F: PrimeField> BenchCircuit<F> for Fibonacci3Circuit<F> {
             a = c;
         }
 
-        b
+        vec![b]
     }
 } 
 
@@ -48,12 +50,12 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for Fibonacci3Circuit<F> {
         let mut v_val: Vec<F> = Vec::new();
         let mut v: Vec<Variable> = Vec::new();
 
-        // Add a 0 to the witness
-        v_val.push(<F>::from(0 as u32));
-        v.push(cs.new_witness_variable(|| Ok(v_val.last().unwrap().clone()))?);
+        // Add x (0) to the witness
+        v_val.push(self.x);
+        v.push(cs.new_witness_variable(|| Ok(*v_val.last().unwrap()))?);
 
         // Add a 1 to the witness
-        let one_val = <F>::from(1 as u32);
+        let one_val = <F>::from(1u32);
         let one = cs.new_witness_variable(|| Ok(one_val))?;
         v_val.push(one_val);
         v.push(one);
@@ -61,7 +63,7 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for Fibonacci3Circuit<F> {
         for i in 0..(self.t - 2) {
             // The next value is the sum of the two lasts
             v_val.push(v_val[v_val.len() - 1] + v_val[v_val.len() - 2]);
-            v.push(cs.new_witness_variable(|| Ok(v_val.last().unwrap().clone()))?);
+            v.push(cs.new_witness_variable(|| Ok(*v_val.last().unwrap()))?);
 
 
             if i % 2 == 0 {
@@ -74,7 +76,7 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for Fibonacci3Circuit<F> {
         let out_val = v_val[v_val.len() - 1] + v_val[v_val.len() - 2];
         let out =  cs.new_input_variable(|| Ok(out_val))?;
 
-        cs.enforce_constraint(lc!() + v[v.len() - 1] + v[v.len() - 2], lc!() + one, lc!() + out);
+        cs.enforce_constraint(lc!() + v[v.len() - 1] + v[v.len() - 2], lc!() + one, lc!() + out)?;
 
         Ok(())
     }
@@ -0,0 +1,235 @@
+//! Circuits compiled by circom, read from an iden3 `.r1cs` file and the
+//! `.wtns` witness next to it.
+//!
+//! A circuit name ending in `.r1cs` is the path of such a file, so it can be
+//! used anywhere a registered circuit can. The witness of `path/circuit.r1cs`
+//! is read from `path/circuit.wtns`. The files do not depend on the number of
+//! rounds, which is ignored.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use ark_ff::{BigInteger, FpParameters, PrimeField};
+use ark_relations::{
+    lc,
+    r1cs::{ConstraintSynthesizer, ConstraintSystemRef, LinearCombination, SynthesisError, Variable},
+};
+use ark_std::rand::RngCore;
+use blake2::{Blake2s, Digest};
+use num_bigint::BigUint;
+
+use crate::circuit_traits::BenchCircuit;
+use crate::print_panic;
+
+// Terms of a linear combination as (wire, little endian coefficient)
+type Terms = Vec<(usize, Vec<u8>)>;
+
+// A, B and C of a constraint as (coefficient, wire), like the matrices of arkworks
+type Constraint<F> = [Vec<(F, usize)>; 3];
+
+/// Contents of the .r1cs and .wtns files, before choosing a field
+pub struct R1csFile {
+    prime: BigUint,
+    // Public outputs and inputs, which follow the constant one
+    num_public: usize,
+    constraints: Vec<[Terms; 3]>,
+    witness: Vec<Vec<u8>>,
+}
+
+/// Whether the circuit name is the path of a circom file
+pub fn is_file(name: &str) -> bool {
+    name.ends_with(".r1cs")
+}
+
+/// Name of the circuit without its directory, to show in summaries
+pub fn short_name(name: &str) -> &str {
+    match is_file(name) {
+        true => Path::new(name).file_stem().and_then(|stem| stem.to_str()).unwrap_or(name),
+        false => name,
+    }
+}
+
+/// Name that tells the results of the circuit apart. Circuit files with the
+/// same stem in different directories get a digest of their canonical path.
+pub fn result_name(name: &str) -> String {
+    match is_file(name) {
+        true => {
+            let path = fs::canonicalize(name).unwrap_or_else(|_| Path::new(name).to_path_buf());
+            let digest = Blake2s::digest(path.to_string_lossy().as_bytes());
+            format!("{}-{}", short_name(name), hex::encode(&digest[..4]))
+        }
+        false => name.to_string(),
+    }
+}
+
+// Little endian reader of the sections of the iden3 binary formats
+struct Reader<'a> {
+    path: &'a str,
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> &'a [u8] {
+        if self.data.len() < len {
+            print_panic!("{} is truncated", self.path)
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        bytes
+    }
+
+    fn u32(&mut self) -> usize {
+        u32::from_le_bytes(self.bytes(4).try_into().unwrap()) as usize
+    }
+
+    fn u64(&mut self) -> usize {
+        u64::from_le_bytes(self.bytes(8).try_into().unwrap()) as usize
+    }
+}
+
+// Sections of a file by type, checking its magic bytes
+fn sections(path: &str, magic: &[u8; 4]) -> HashMap<usize, Vec<u8>> {
+    let content = fs::read(path).unwrap_or_else(|e| print_panic!("Cannot read {}: {}", path, e));
+    let mut reader = Reader { path, data: &content };
+    if reader.bytes(4) != magic {
+        print_panic!("{} is not a {} file", path, String::from_utf8_lossy(magic))
+    }
+    let _version = reader.u32();
+    let mut sections = HashMap::new();
+    for _ in 0..reader.u32() {
+        let kind = reader.u32();
+        let len = reader.u64();
+        sections.insert(kind, reader.bytes(len).to_vec());
+    }
+    sections
+}
+
+fn section<'a>(path: &'a str, sections: &'a HashMap<usize, Vec<u8>>, kind: usize) -> Reader<'a> {
+    let data = sections
+        .get(&kind)
+        .unwrap_or_else(|| print_panic!("{} has no section of type {}", path, kind));
+    Reader { path, data }
+}
+
+/// Reads the circuit at `path` and its witness
+pub fn read(path: &str) -> R1csFile {
+    let r1cs = sections(path, b"r1cs");
+    if r1cs.contains_key(&4) || r1cs.contains_key(&5) {
+        print_panic!("{} has custom gates, which R1CS cannot express", path)
+    }
+
+    let mut header = section(path, &r1cs, 1);
+    let n8 = header.u32();
+    let prime = BigUint::from_bytes_le(header.bytes(n8));
+    let num_wires = header.u32();
+    let num_public = header.u32() + header.u32();
+    let _num_private_inputs = header.u32();
+    let _num_labels = header.u64();
+    let num_constraints = header.u32();
+    // Wire 0 is the constant one, so the public values need one wire less
+    if num_public >= num_wires {
+        print_panic!("{} declares {} public values but only {} wires", path, num_public, num_wires)
+    }
+
+    // Values at least the prime would silently wrap around in the field
+    let canonical = |path: &str, bytes: &[u8]| {
+        if BigUint::from_bytes_le(bytes) >= prime {
+            print_panic!("{} has a value that is not smaller than the prime of its field", path)
+        }
+        bytes.to_vec()
+    };
+
+    let mut reader = section(path, &r1cs, 2);
+    let mut terms = || -> Terms {
+        (0..reader.u32())
+            .map(|_| match reader.u32() {
+                wire if wire < num_wires => (wire, canonical(path, reader.bytes(n8))),
+                wire => print_panic!("{} uses wire {} of {}", path, wire, num_wires),
+            })
+            .collect()
+    };
+    let constraints = (0..num_constraints).map(|_| [terms(), terms(), terms()]).collect();
+
+    let wtns_path = Path::new(path).with_extension("wtns");
+    let wtns_path = wtns_path.to_string_lossy();
+    let wtns = sections(&wtns_path, b"wtns");
+    let mut header = section(&wtns_path, &wtns, 1);
+    let wtns_n8 = header.u32();
+    if wtns_n8 != n8 || BigUint::from_bytes_le(header.bytes(n8)) != prime {
+        print_panic!("The field of {} is not the one of {}", wtns_path, path)
+    }
+    if header.u32() != num_wires {
+        print_panic!("{} does not assign the {} wires of {}", wtns_path, num_wires, path)
+    }
+    let mut values = section(&wtns_path, &wtns, 2);
+    if values.data.len() != num_wires * n8 {
+        print_panic!("{} has {} bytes of values instead of {} for {} wires", wtns_path, values.data.len(), num_wires * n8, num_wires)
+    }
+    let witness: Vec<Vec<u8>> = (0..num_wires).map(|_| canonical(&wtns_path, values.bytes(n8))).collect();
+    // Wire 0 becomes the constant one of arkworks, whatever the file assigns it
+    if witness.first().map(|one| BigUint::from_bytes_le(one)) != Some(BigUint::from(1u8)) {
+        print_panic!("Wire 0 of {} is not the constant one", wtns_path)
+    }
+
+    R1csFile { prime, num_public, constraints, witness }
+}
+
+/// Circuit read from a circuit file, with its coefficients and witness in the
+/// field `F`
+#[derive(Clone)]
+pub struct FileCircuit<F> {
+    num_public: usize,
+    constraints: Arc<Vec<Constraint<F>>>,
+    witness: Arc<Vec<F>>,
+}
+
+impl<F: PrimeField> BenchCircuit<F> for FileCircuit<F> {
+    type Params = R1csFile;
+
+    fn new_random<R: RngCore>(file: &R1csFile, _rng: &mut R, _rounds: usize) -> Self {
+        if file.prime != BigUint::from_bytes_le(&F::Params::MODULUS.to_bytes_le()) {
+            print_panic!("The circuit file was compiled for another field than the one of this curve")
+        }
+
+        let element = |bytes: &Vec<u8>| F::from_le_bytes_mod_order(bytes);
+        FileCircuit {
+            num_public: file.num_public,
+            constraints: Arc::new(
+                file.constraints
+                    .iter()
+                    .map(|lcs| lcs.each_ref().map(|terms| terms.iter().map(|(wire, coeff)| (element(coeff), *wire)).collect()))
+                    .collect(),
+            ),
+            witness: Arc::new(file.witness.iter().map(element).collect()),
+        }
+    }
+
+    fn public_inputs(&self) -> Vec<F> {
+        self.witness[1..=self.num_public].to_vec()
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for FileCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        // Wire 0 is the constant one, then come the public values and the
+        // private inputs and internal wires
+        let mut wires = vec![Variable::One];
+        for (i, value) in self.witness.iter().enumerate().skip(1) {
+            wires.push(match i <= self.num_public {
+                true => cs.new_input_variable(|| Ok(*value))?,
+                false => cs.new_witness_variable(|| Ok(*value))?,
+            });
+        }
+
+        let lc = |terms: &Vec<(F, usize)>| {
+            terms.iter().fold(lc!(), |lc: LinearCombination<F>, (coeff, wire)| lc + (*coeff, wires[*wire]))
+        };
+        for [a, b, c] in self.constraints.iter() {
+            cs.enforce_constraint(lc(a), lc(b), lc(c))?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use ark_ff::PrimeField;
+use ark_relations::r1cs::ConstraintMatrices;
+use ark_serialize::CanonicalSerialize;
+use blake2::{Blake2s, Digest};
+use serde::{Deserialize, Serialize};
+
+/// Blake2s digests of the values that should be identical for the same seed
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fingerprints {
+    pub matrices: String,
+    pub srs: String,
+    pub verifying_key: String,
+    pub proof: String,
+}
+
+/// Hex Blake2s digest of the uncompressed serialization of `value`
+pub fn of<T: CanonicalSerialize>(value: &T) -> String {
+    let mut bytes = Vec::new();
+    value.serialize_uncompressed(&mut bytes).unwrap();
+    hex::encode(Blake2s::digest(&bytes))
+}
+
+/// Hex Blake2s digest of the A, B and C matrices
+pub fn of_matrices<F: PrimeField>(matrices: &ConstraintMatrices<F>) -> String {
+    let mut hasher = Blake2s::new();
+    for matrix in [&matrices.a, &matrices.b, &matrices.c] {
+        hasher.update((matrix.len() as u64).to_le_bytes());
+        for row in matrix {
+            hasher.update((row.len() as u64).to_le_bytes());
+            for (value, column) in row {
+                let mut bytes = Vec::new();
+                value.serialize_uncompressed(&mut bytes).unwrap();
+                hasher.update(&bytes);
+                hasher.update((*column as u64).to_le_bytes());
+            }
+        }
+    }
+    hex::encode(hasher.finalize())
+}
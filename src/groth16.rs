@@ -0,0 +1,125 @@
+//! Groth16 from ark-groth16 0.3 on the same circuits and pairing engines, to
+//! compare Marlin with a proving system that has a circuit-specific setup.
+
+use std::time::Instant;
+
+use ark_ec::PairingEngine;
+use ark_groth16::{create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof};
+
+use crate::bench::synthesize;
+use crate::circuit_traits::BenchCircuit;
+use crate::constraint_stats::ConstraintStats;
+use crate::fingerprint::{self, Fingerprints};
+use crate::instrumented::{IndexerPhases, ProverPhases, VerifierPhases};
+use crate::memory::{self, MemoryPhases};
+use crate::public_inputs::WithPublicInputs;
+use crate::report::{Backend, RunResult};
+use crate::seed::{self, Stream};
+use crate::sizes::{ArtifactSizes, Size};
+use crate::stats::PhaseStats;
+use crate::threads;
+use crate::RunConfig;
+
+/// Runs the Groth16 setup, prover and verifier for the circuit `C` over the
+/// pairing engine `E`
+pub fn bench<E: PairingEngine, C: BenchCircuit<E::Fr>>(config: &RunConfig, params: &C::Params) -> RunResult {
+    let circuit_rng = &mut seed::rng(config.seed, Stream::Circuit);
+    let c = WithPublicInputs::new(C::new_random(params, circuit_rng, config.rounds), config.public_inputs, circuit_rng);
+    let inputs = c.public_inputs();
+    let prover_rng = &mut seed::prover_rng(config.seed, config.prover_variant);
+
+    let mut memory = MemoryPhases::default();
+
+    let (matrices, synthesis_memory) = synthesize(&c);
+    memory.synthesis = synthesis_memory;
+    // The domains of Marlin mean nothing for Groth16
+    let constraint_system = ConstraintStats::of_matrices(&matrices);
+
+    let mut setup_samples = Vec::new();
+    let mut prover_samples = Vec::new();
+    let mut verifier_samples = Vec::new();
+    let mut verified = true;
+    let mut artifacts = None;
+
+    for i in 0..(config.warmup + config.iterations) {
+        // The setup depends on the circuit, so it is repeated like the indexer of Marlin
+        let ((pk, setup_time), setup_memory) = memory::measure(|| {
+            let start = Instant::now();
+            let pk = generate_random_parameters::<E, _, _>(c.clone(), &mut seed::rng(config.seed, Stream::Setup))
+                .unwrap();
+            (pk, start.elapsed())
+        });
+
+        let ((proof, prover_time), prove_memory) = memory::measure(|| {
+            let start = Instant::now();
+            let proof = create_random_proof(c.clone(), &pk, prover_rng).unwrap();
+            (proof, start.elapsed())
+        });
+
+        // Preparing the verifying key is part of the verification, as Marlin
+        // verifies from its plain verifying key
+        let ((res, verifier_time), verify_memory) = memory::measure(|| {
+            let start = Instant::now();
+            let pvk = prepare_verifying_key(&pk.vk);
+            let res = verify_proof(&pvk, &proof, &inputs).unwrap();
+            (res, start.elapsed())
+        });
+        verified &= res;
+
+        memory.setup = setup_memory;
+        memory.prove = prove_memory;
+        memory.verify = verify_memory;
+
+        if i >= config.warmup {
+            setup_samples.push(setup_time.as_nanos() as u64);
+            prover_samples.push(prover_time.as_nanos() as u64);
+            verifier_samples.push(verifier_time.as_nanos() as u64);
+        }
+        artifacts = Some((pk, proof));
+    }
+
+    let (pk, proof) = artifacts.unwrap();
+    let sizes = ArtifactSizes {
+        proving_key: Size::of(&pk),
+        verifying_key: Size::of(&pk.vk),
+        proof: Size::of(&proof),
+        ..Default::default()
+    };
+    let fingerprints = Fingerprints {
+        matrices: fingerprint::of_matrices(&matrices),
+        srs: String::new(),
+        verifying_key: fingerprint::of(&pk.vk),
+        proof: fingerprint::of(&proof),
+    };
+
+    RunResult {
+        backend: Backend::Groth16,
+        pc: None,
+        fs: None,
+        circuit: config.circuit.clone(),
+        curve: config.curve.clone(),
+        rounds: config.rounds,
+        public_inputs: config.public_inputs,
+        seed: config.seed,
+        threads: threads::current(),
+        warmup: config.warmup,
+        srs_max_degree: 0,
+        srs_cached: false,
+        iterations: config.iterations,
+        constraint_system,
+        setup: PhaseStats::from_samples(setup_samples),
+        srs_load: PhaseStats::default(),
+        indexer: PhaseStats::default(),
+        prover: PhaseStats::from_samples(prover_samples),
+        verifier: PhaseStats::from_samples(verifier_samples),
+        indexer_phases: IndexerPhases::default(),
+        prover_phases: ProverPhases::default(),
+        verifier_phases: VerifierPhases::default(),
+        sizes,
+        memory: config.memory.then_some(memory),
+        fingerprints,
+        soundness: Vec::new(),
+        batch: None,
+        verified,
+    }
+}
@@ -0,0 +1,454 @@
+//! Copy of `Marlin::index`, `Marlin::prove` and `Marlin::verify` from
+//! ark-marlin 0.3 that records how long each internal phase takes.
+//!
+//! The Fiat-Shamir transcript is a type parameter instead of a digest. With
+//! `FiatShamirRng<D>` the keys and proofs are the same ones
+//! `ark_marlin::Marlin<_, _, D>` produces, so they can be mixed freely with the
+//! upstream implementation.
+
+use std::marker::PhantomData;
+use std::time::{Duration, Instant};
+
+use ark_ff::{to_bytes, PrimeField, UniformRand};
+use ark_marlin::ahp::EvaluationsProvider;
+use ark_marlin::{AHPForR1CS, Error, IndexProverKey, IndexVerifierKey, Proof, UniversalSRS};
+use ark_poly::{univariate::DensePolynomial, EvaluationDomain, GeneralEvaluationDomain};
+use ark_poly_commit::{
+    Evaluations, LabeledCommitment, LinearCombination, PCUniversalParams, PolynomialCommitment, QuerySet,
+};
+use ark_relations::r1cs::ConstraintSynthesizer;
+use ark_std::rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::stats::PhaseStats;
+use crate::transcript::Transcript;
+
+// Defines the per-iteration timings of a step and their statistics
+macro_rules! phases {
+    ($timings:ident, $stats:ident { $($phase:ident),* $(,)? }) => {
+        #[derive(Clone, Copy, Debug, Default)]
+        pub struct $timings {
+            $(pub $phase: Duration,)*
+        }
+
+        #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+        pub struct $stats {
+            $(pub $phase: PhaseStats,)*
+        }
+
+        impl $stats {
+            pub fn from_timings(timings: &[$timings]) -> Self {
+                $stats {
+                    $($phase: PhaseStats::from_samples(
+                        timings.iter().map(|t| t.$phase.as_nanos() as u64).collect()
+                    ),)*
+                }
+            }
+
+            pub fn summaries(&self) -> Vec<(&'static str, String)> {
+                vec![$((stringify!($phase), self.$phase.summary()),)*]
+            }
+
+            pub fn stats(&self) -> Vec<(&'static str, &PhaseStats)> {
+                vec![$((stringify!($phase), &self.$phase),)*]
+            }
+        }
+    };
+}
+
+phases!(IndexerTimings, IndexerPhases {
+    ahp_index,
+    trim,
+    commit,
+});
+
+phases!(ProverTimings, ProverPhases {
+    init,
+    first_round,
+    first_commit,
+    second_round,
+    second_commit,
+    third_round,
+    third_commit,
+    evaluation,
+    opening,
+});
+
+phases!(VerifierTimings, VerifierPhases {
+    ahp,
+    pc_check,
+});
+
+// Runs `f` adding the time it takes to `elapsed`
+fn timed<T>(elapsed: &mut Duration, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = f();
+    *elapsed += start.elapsed();
+    result
+}
+
+/// Inputs of the polynomial commitment check that ends the verification
+pub struct PreparedCheck<F: PrimeField, PC: PolynomialCommitment<F, DensePolynomial<F>>> {
+    pub lc_s: Vec<LinearCombination<F>>,
+    pub commitments: Vec<LabeledCommitment<PC::Commitment>>,
+    pub query_set: QuerySet<F>,
+    pub evaluations: Evaluations<F, F>,
+    pub opening_challenge: F,
+}
+
+pub struct InstrumentedMarlin<F: PrimeField, PC: PolynomialCommitment<F, DensePolynomial<F>>, FS: Transcript>(
+    PhantomData<(F, PC, FS)>,
+);
+
+impl<F: PrimeField, PC: PolynomialCommitment<F, DensePolynomial<F>>, FS: Transcript> InstrumentedMarlin<F, PC, FS> {
+    pub const PROTOCOL_NAME: &'static [u8] = b"MARLIN-2019";
+
+    /// Same as `Marlin::index`
+    #[allow(clippy::type_complexity)]
+    pub fn index<C: ConstraintSynthesizer<F>>(
+        srs: &UniversalSRS<F, PC>,
+        c: C,
+        timings: &mut IndexerTimings,
+    ) -> Result<(IndexProverKey<F, PC>, IndexVerifierKey<F, PC>), Error<PC::Error>> {
+        let index = timed(&mut timings.ahp_index, || AHPForR1CS::index(c))?;
+        if srs.max_degree() < index.max_degree() {
+            return Err(Error::IndexTooLarge);
+        }
+
+        let coeff_support = AHPForR1CS::get_degree_bounds(&index.index_info);
+        // Marlin only needs degree 2 random polynomials
+        let supported_hiding_bound = 1;
+        let (committer_key, verifier_key) = timed(&mut timings.trim, || {
+            PC::trim(srs, index.max_degree(), supported_hiding_bound, Some(&coeff_support))
+        })
+        .map_err(Error::from_pc_err)?;
+
+        let (index_comms, index_comm_rands) = timed(&mut timings.commit, || {
+            PC::commit(&committer_key, index.iter(), None)
+        })
+        .map_err(Error::from_pc_err)?;
+
+        let index_comms = index_comms.into_iter().map(|c| c.commitment().clone()).collect();
+        let index_vk = IndexVerifierKey {
+            index_info: index.index_info,
+            index_comms,
+            verifier_key,
+        };
+
+        let index_pk = IndexProverKey {
+            index,
+            index_comm_rands,
+            index_vk: index_vk.clone(),
+            committer_key,
+        };
+
+        Ok((index_pk, index_vk))
+    }
+
+    /// Same as `Marlin::prove`
+    pub fn prove<C: ConstraintSynthesizer<F>, R: RngCore>(
+        index_pk: &IndexProverKey<F, PC>,
+        c: C,
+        zk_rng: &mut R,
+        timings: &mut ProverTimings,
+    ) -> Result<Proof<F, PC>, Error<PC::Error>> {
+        let prover_init_state = timed(&mut timings.init, || AHPForR1CS::prover_init(&index_pk.index, c))?;
+        let public_input = prover_init_state.public_input();
+        let mut fs_rng = FS::from_seed(
+            &to_bytes![&Self::PROTOCOL_NAME, &index_pk.index_vk, &public_input].unwrap(),
+        );
+
+        // First round
+        let (prover_first_msg, prover_first_oracles, prover_state) = timed(&mut timings.first_round, || {
+            AHPForR1CS::prover_first_round(prover_init_state, zk_rng)
+        })?;
+
+        let (first_comms, first_comm_rands) = timed(&mut timings.first_commit, || {
+            PC::commit(&index_pk.committer_key, prover_first_oracles.iter(), Some(zk_rng))
+        })
+        .map_err(Error::from_pc_err)?;
+
+        fs_rng.absorb(&to_bytes![first_comms, prover_first_msg].unwrap());
+
+        let (verifier_first_msg, verifier_state) =
+            AHPForR1CS::verifier_first_round(index_pk.index_vk.index_info, &mut fs_rng)?;
+
+        // Second round
+        let (prover_second_msg, prover_second_oracles, prover_state) = timed(&mut timings.second_round, || {
+            AHPForR1CS::prover_second_round(&verifier_first_msg, prover_state, zk_rng)
+        });
+
+        let (second_comms, second_comm_rands) = timed(&mut timings.second_commit, || {
+            PC::commit(&index_pk.committer_key, prover_second_oracles.iter(), Some(zk_rng))
+        })
+        .map_err(Error::from_pc_err)?;
+
+        fs_rng.absorb(&to_bytes![second_comms, prover_second_msg].unwrap());
+
+        let (verifier_second_msg, verifier_state) =
+            AHPForR1CS::verifier_second_round(verifier_state, &mut fs_rng);
+
+        // Third round
+        let (prover_third_msg, prover_third_oracles) = timed(&mut timings.third_round, || {
+            AHPForR1CS::prover_third_round(&verifier_second_msg, prover_state, zk_rng)
+        })?;
+
+        let (third_comms, third_comm_rands) = timed(&mut timings.third_commit, || {
+            PC::commit(&index_pk.committer_key, prover_third_oracles.iter(), Some(zk_rng))
+        })
+        .map_err(Error::from_pc_err)?;
+
+        fs_rng.absorb(&to_bytes![third_comms, prover_third_msg].unwrap());
+
+        let verifier_state = AHPForR1CS::verifier_third_round(verifier_state, &mut fs_rng);
+
+        // Gather prover polynomials in one vector.
+        let polynomials: Vec<_> = index_pk
+            .index
+            .iter()
+            .chain(prover_first_oracles.iter())
+            .chain(prover_second_oracles.iter())
+            .chain(prover_third_oracles.iter())
+            .collect();
+
+        // Gather commitments in one vector.
+        let commitments = vec![
+            first_comms.iter().map(|p| p.commitment().clone()).collect(),
+            second_comms.iter().map(|p| p.commitment().clone()).collect(),
+            third_comms.iter().map(|p| p.commitment().clone()).collect(),
+        ];
+        let labeled_comms: Vec<_> = index_pk
+            .index_vk
+            .iter()
+            .cloned()
+            .zip(&AHPForR1CS::<F>::INDEXER_POLYNOMIALS)
+            .map(|(c, l)| LabeledCommitment::new(l.to_string(), c, None))
+            .chain(first_comms.iter().cloned())
+            .chain(second_comms.iter().cloned())
+            .chain(third_comms.iter().cloned())
+            .collect();
+
+        // Gather commitment randomness together.
+        let comm_rands: Vec<PC::Randomness> = index_pk
+            .index_comm_rands
+            .clone()
+            .into_iter()
+            .chain(first_comm_rands)
+            .chain(second_comm_rands)
+            .chain(third_comm_rands)
+            .collect();
+
+        // Compute the AHP verifier's query set.
+        let (query_set, verifier_state) = AHPForR1CS::verifier_query_set(verifier_state, &mut fs_rng);
+
+        let start = Instant::now();
+        let lc_s = AHPForR1CS::construct_linear_combinations(&public_input, &polynomials, &verifier_state)?;
+
+        let mut evaluations = Vec::new();
+        for (label, (_, point)) in &query_set {
+            let lc = lc_s
+                .iter()
+                .find(|lc| &lc.label == label)
+                .ok_or_else(|| ark_marlin::ahp::Error::MissingEval(label.to_string()))?;
+            let eval = polynomials.get_lc_eval(lc, *point)?;
+            if !AHPForR1CS::<F>::LC_WITH_ZERO_EVAL.contains(&lc.label.as_ref()) {
+                evaluations.push((label.to_string(), eval));
+            }
+        }
+
+        evaluations.sort_by(|a, b| a.0.cmp(&b.0));
+        let evaluations = evaluations.into_iter().map(|x| x.1).collect::<Vec<F>>();
+        timings.evaluation += start.elapsed();
+
+        fs_rng.absorb(&evaluations);
+        let opening_challenge: F = u128::rand(&mut fs_rng).into();
+
+        let pc_proof = timed(&mut timings.opening, || {
+            PC::open_combinations(
+                &index_pk.committer_key,
+                &lc_s,
+                polynomials,
+                &labeled_comms,
+                &query_set,
+                opening_challenge,
+                &comm_rands,
+                Some(zk_rng),
+            )
+        })
+        .map_err(Error::from_pc_err)?;
+
+        // Gather prover messages together.
+        let prover_messages = vec![prover_first_msg, prover_second_msg, prover_third_msg];
+
+        Ok(Proof::new(commitments, evaluations, prover_messages, pc_proof))
+    }
+
+    /// Same as `Marlin::verify`
+    pub fn verify<R: RngCore>(
+        index_vk: &IndexVerifierKey<F, PC>,
+        public_input: &[F],
+        proof: &Proof<F, PC>,
+        rng: &mut R,
+        timings: &mut VerifierTimings,
+    ) -> Result<bool, Error<PC::Error>> {
+        let check = timed(&mut timings.ahp, || Self::prepare_check(index_vk, public_input, proof))?;
+
+        timed(&mut timings.pc_check, || {
+            PC::check_combinations(
+                &index_vk.verifier_key,
+                &check.lc_s,
+                &check.commitments,
+                &check.query_set,
+                &check.evaluations,
+                &proof.pc_proof,
+                check.opening_challenge,
+                rng,
+            )
+        })
+        .map_err(Error::from_pc_err)
+    }
+
+    /// Runs the AHP verifier, leaving out the final check of the polynomial
+    /// commitments so that several proofs can be checked together
+    pub fn prepare_check(
+        index_vk: &IndexVerifierKey<F, PC>,
+        public_input: &[F],
+        proof: &Proof<F, PC>,
+    ) -> Result<PreparedCheck<F, PC>, Error<PC::Error>> {
+        let public_input = {
+            let domain_x = GeneralEvaluationDomain::<F>::new(public_input.len() + 1).unwrap();
+
+            let mut unpadded_input = public_input.to_vec();
+            unpadded_input.resize(
+                core::cmp::max(public_input.len(), domain_x.size() - 1),
+                F::zero(),
+            );
+
+            unpadded_input
+        };
+
+        let mut fs_rng = FS::from_seed(
+            &to_bytes![&Self::PROTOCOL_NAME, &index_vk, &public_input].unwrap(),
+        );
+
+        // First round
+        let first_comms = &proof.commitments[0];
+        fs_rng.absorb(&to_bytes![first_comms, proof.prover_messages[0]].unwrap());
+
+        let (_, verifier_state) = AHPForR1CS::verifier_first_round(index_vk.index_info, &mut fs_rng)?;
+
+        // Second round
+        let second_comms = &proof.commitments[1];
+        fs_rng.absorb(&to_bytes![second_comms, proof.prover_messages[1]].unwrap());
+
+        let (_, verifier_state) = AHPForR1CS::verifier_second_round(verifier_state, &mut fs_rng);
+
+        // Third round
+        let third_comms = &proof.commitments[2];
+        fs_rng.absorb(&to_bytes![third_comms, proof.prover_messages[2]].unwrap());
+
+        let verifier_state = AHPForR1CS::verifier_third_round(verifier_state, &mut fs_rng);
+
+        // Collect degree bounds for commitments. Indexed polynomials have *no*
+        // degree bounds because we know the committed index polynomial has the
+        // correct degree.
+        let index_info = index_vk.index_info;
+        let degree_bounds = vec![None; index_vk.index_comms.len()]
+            .into_iter()
+            .chain(AHPForR1CS::prover_first_round_degree_bounds(&index_info))
+            .chain(AHPForR1CS::prover_second_round_degree_bounds(&index_info))
+            .chain(AHPForR1CS::prover_third_round_degree_bounds(&index_info))
+            .collect::<Vec<_>>();
+
+        // Gather commitments in one vector.
+        let polynomial_labels = AHPForR1CS::<F>::INDEXER_POLYNOMIALS
+            .iter()
+            .chain(&AHPForR1CS::<F>::PROVER_POLYNOMIALS)
+            .map(|s| s.to_string());
+        let commitments: Vec<_> = index_vk
+            .iter()
+            .chain(first_comms)
+            .chain(second_comms)
+            .chain(third_comms)
+            .cloned()
+            .zip(polynomial_labels)
+            .zip(degree_bounds)
+            .map(|((c, l), d)| LabeledCommitment::new(l, c, d))
+            .collect();
+
+        let (query_set, verifier_state) = AHPForR1CS::verifier_query_set(verifier_state, &mut fs_rng);
+
+        fs_rng.absorb(&proof.evaluations);
+        let opening_challenge: F = u128::rand(&mut fs_rng).into();
+
+        let mut evaluations = Evaluations::new();
+        let mut evaluation_labels = Vec::new();
+        for (poly_label, (_, point)) in query_set.iter().cloned() {
+            if AHPForR1CS::<F>::LC_WITH_ZERO_EVAL.contains(&poly_label.as_ref()) {
+                evaluations.insert((poly_label, point), F::zero());
+            } else {
+                evaluation_labels.push((poly_label, point));
+            }
+        }
+        evaluation_labels.sort_by(|a, b| a.0.cmp(&b.0));
+        for (q, eval) in evaluation_labels.into_iter().zip(&proof.evaluations) {
+            evaluations.insert(q, *eval);
+        }
+
+        let lc_s = AHPForR1CS::construct_linear_combinations(&public_input, &evaluations, &verifier_state)?;
+
+        Ok(PreparedCheck {
+            lc_s,
+            commitments,
+            query_set,
+            evaluations,
+            opening_challenge,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly_commit::PolynomialCommitment;
+    use ark_serialize::CanonicalSerialize;
+
+    use super::{IndexerTimings, InstrumentedMarlin, ProverTimings, VerifierTimings};
+    use crate::basic_circuit::BasicCircuit;
+    use crate::bench::{synthesize, UpstreamMarlin, PC};
+    use crate::circuit_traits::BenchCircuit;
+    use crate::seed::{self, Stream};
+    use crate::srs;
+    use crate::transcript::Blake2sTranscript;
+
+    type Instrumented = InstrumentedMarlin<Fr, PC<Bls12_381>, Blake2sTranscript>;
+
+    fn bytes<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        value.serialize_uncompressed(&mut bytes).unwrap();
+        bytes
+    }
+
+    // The timed copy must build the keys and proofs upstream Marlin builds
+    // with the same seed, and each must accept the proofs of the other
+    #[test]
+    fn same_as_upstream() {
+        let c = BasicCircuit::<Fr>::new_random(&(), &mut seed::rng(0, Stream::Circuit), 4);
+        let inputs = c.public_inputs();
+        let (matrices, _) = synthesize(&c);
+        let srs = PC::<Bls12_381>::setup(srs::required_degree(&matrices), None, &mut seed::rng(0, Stream::Setup)).unwrap();
+
+        let (pk, vk) = Instrumented::index(&srs, c, &mut IndexerTimings::default()).unwrap();
+        let (upstream_pk, upstream_vk) = UpstreamMarlin::<Bls12_381>::index(&srs, c).unwrap();
+        assert_eq!(bytes(&pk), bytes(&upstream_pk));
+        assert_eq!(bytes(&vk), bytes(&upstream_vk));
+
+        let proof = Instrumented::prove(&pk, c, &mut seed::prover_rng(0, 0), &mut ProverTimings::default()).unwrap();
+        let upstream_proof = UpstreamMarlin::<Bls12_381>::prove(&upstream_pk, c, &mut seed::prover_rng(0, 0)).unwrap();
+        assert_eq!(bytes(&proof), bytes(&upstream_proof));
+
+        let verifier_rng = &mut seed::rng(0, Stream::Verifier);
+        assert!(Instrumented::verify(&vk, &inputs, &upstream_proof, verifier_rng, &mut VerifierTimings::default()).unwrap());
+        assert!(UpstreamMarlin::<Bls12_381>::verify(&upstream_vk, &inputs, &proof, verifier_rng).unwrap());
+    }
+}
@@ -1,7 +1,32 @@
-use ark_ff::PrimeField;
-use clap::{Parser, arg, command};
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
 
 mod printers;
+mod report;
+mod stats;
+mod constraint_stats;
+mod sizes;
+mod memory;
+mod srs;
+mod seed;
+mod fingerprint;
+mod reproducible;
+mod threads;
+mod soundness;
+mod batch;
+mod pc;
+mod transcript;
+mod universal;
+mod scaling;
+mod sweep;
+mod artifact;
+mod steps;
+mod export;
+mod bench;
+mod groth16;
+mod instrumented;
+mod registry;
 mod circuit_traits;
 mod basic_circuit;
 mod addition_circuit;
@@ -12,254 +37,359 @@ mod fibonacci2_circuit;
 mod fibonacci3_circuit;
 mod sum_circuit;
 mod sumprod_circuit;
+mod file_circuit;
+mod public_inputs;
 
 use printers::*;
-
-use ark_relations::r1cs::{ConstraintSystem, ConstraintSynthesizer, OptimizationGoal};
-use circuit_traits::BenchCircuit;
-use basic_circuit::BasicCircuit;
-use addition_circuit::AdditionCircuit;
-use product_circuit::ProductCircuit;
-use dense_circuit::DenseCircuit;
-use fibonacci_circuit::FibonacciCircuit;
-use fibonacci2_circuit::Fibonacci2Circuit;
-use fibonacci3_circuit::Fibonacci3Circuit;
-use sum_circuit::SumCircuit;
-use sumprod_circuit::SumProdCircuit;
-
-use ark_marlin::Marlin;
-
-use ark_poly_commit::marlin_pc::MarlinKZG10;
+use pc::{BenchPC, PcScheme};
+use report::{Backend, OutputFormat, RunResult};
+use transcript::{
+    Blake2bTranscript, Blake2sTranscript, FsScheme, Keccak256Transcript, PoseidonTranscript, Sha256Transcript,
+};
+use sweep::SweepArgs;
+
+use ark_ec::{AffineCurve, PairingEngine};
+use ark_ff::PrimeField;
 use ark_poly::univariate::DensePolynomial;
 use blake2::Blake2s;
+use ark_poly_commit::ipa_pc::InnerProductArgPC;
+use ark_poly_commit::marlin_pc::MarlinKZG10;
+use ark_poly_commit::sonic_pc::SonicKZG10;
+use circuit_traits::BenchCircuit;
+use registry::{CircuitVisitor, CurveVisitor, GroupVisitor, CIRCUITS, CURVES, GROUPS};
 
-use ark_bls12_377::{Fr as Bls377Fr, Bls12_377};
-use ark_bls12_381::{Fr as Bls381Fr, Bls12_381};
-use ark_mnt4_298::{Fr as MNT4Fr, MNT4_298};
-use ark_mnt4_753::{Fr as MNT4BigFr, MNT4_753};
-use ark_mnt6_298::{Fr as MNT6Fr, MNT6_298};
-use ark_mnt6_753::{Fr as MNT6BigFr, MNT6_753};
-
-use num_bigint::BigUint;
+#[global_allocator]
+static ALLOCATOR: memory::CountingAllocator = memory::CountingAllocator;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Circuit to test
-    #[arg(short, long, default_value = "basic", 
-        help = "Options: addition, product, dense, fibonacci, fibonacci1, fibonacci2, fibonacci3, sum, sumprod"
-    )]
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Circuit to test, see --list-circuits
+    #[arg(short, long, default_value = "basic")]
     system: String,
 
     /// Number of rounds
     #[arg(short, long, default_value_t = 1)]
     rounds: usize,
 
-    // Field used by the system
-    #[arg(short, long, default_value = "bls12_381",
-        help = "Options: bls12_377, bls12_381, mnt4_298, mnt4_753, mnt6_298, mnt6_753"
-    )]
+    /// Curve used by the system, or group with --pc ipa, see --list-curves
+    #[arg(short, long, default_value = "bls12_381")]
     curve: String,
+
+    /// Proving system run on the circuit
+    #[arg(long, value_enum, default_value_t = Backend::Marlin)]
+    backend: Backend,
+
+    /// Polynomial commitment scheme of Marlin
+    #[arg(long, value_enum, default_value_t = PcScheme::MarlinKzg10)]
+    pc: PcScheme,
+
+    /// Fiat-Shamir transcript of Marlin
+    #[arg(long, value_enum, default_value_t = FsScheme::Blake2s)]
+    fs: FsScheme,
+
+    /// Extra public inputs added to the circuit, each bound to a witness variable
+    #[arg(long, default_value_t = 0)]
+    public_inputs: usize,
+
+    /// Untimed repetitions of every phase before measuring
+    #[arg(long, default_value_t = 0)]
+    warmup: usize,
+
+    /// Timed repetitions of every phase
+    #[arg(long, default_value_t = 1)]
+    iterations: usize,
+
+    /// Directory where the SRS is stored and reused between runs
+    #[arg(long)]
+    srs_dir: Option<PathBuf>,
+
+    /// Minimum degree of a generated SRS, so that bigger circuits can reuse it
+    #[arg(long, default_value_t = 0)]
+    srs_max_degree: usize,
+
+    /// Seed of the RNG used for the circuit, the SRS and the proofs
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Run twice with the same seed and check that the results are identical
+    #[arg(long)]
+    check_reproducible: bool,
+
+    /// Threads used by the prover, 0 for one per core. Needs the parallel feature
+    #[arg(long, default_value_t = 0)]
+    threads: usize,
+
+    /// Run with 1, 2, 4, ... threads up to --threads and report the speedups
+    #[arg(long, conflicts_with = "check_reproducible")]
+    scaling: bool,
+
+    /// Measure the memory of every phase, which slows down the allocations
+    #[arg(long)]
+    memory: bool,
+
+    /// Check that the verifier rejects tampered proofs, inputs and keys
+    #[arg(long)]
+    soundness_check: bool,
+
+    /// Also prove this many instances and compare verifying them one by one and as a batch
+    #[arg(long, default_value_t = 0)]
+    batch: usize,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+
+    /// List the available circuits and exit
+    #[arg(long)]
+    list_circuits: bool,
+
+    /// List the available curves and groups and exit
+    #[arg(long)]
+    list_curves: bool,
 }
 
-fn prettify_matrix<T: PrimeField>(num_witness: usize, matrix: Vec<Vec<(T, usize)>>) 
-    -> Vec<Vec<BigUint>> {
-    // The matrix size is the number of witness x constraints
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run every combination of circuits, curves and rounds
+    Sweep(SweepArgs),
+    /// Index several circuits from a single SRS sized for the largest one
+    Universal(universal::UniversalArgs),
+    /// Write the R1CS matrices and the witness of a circuit in the formats of circom
+    Export(export::ExportArgs),
+    #[command(flatten)]
+    Step(steps::Step),
+}
 
-    // Create a new matrix
-    let mut new_matrix: Vec<Vec<BigUint>> = Vec::new();
+/// Parameters of a single benchmark run
+#[derive(Clone, Debug)]
+pub struct RunConfig {
+    pub backend: Backend,
+    // Only used by Marlin
+    pub pc: PcScheme,
+    pub fs: FsScheme,
+    pub circuit: String,
+    pub curve: String,
+    pub rounds: usize,
+    // Extra public inputs added to the circuit
+    pub public_inputs: usize,
+    pub seed: u64,
+    // Selects the zero-knowledge randomness of the prover for the seed
+    pub prover_variant: u64,
+    pub warmup: usize,
+    pub iterations: usize,
+    // Count the allocations of every phase
+    pub memory: bool,
+    pub soundness_check: bool,
+    // Number of proofs verified as a batch, 0 to skip it
+    pub batch: usize,
+    // Size of the thread pool, 0 for one thread per core
+    pub threads: usize,
+    pub srs_dir: Option<PathBuf>,
+    pub srs_max_degree: usize,
+}
 
-    for i in matrix {
-        let mut new_vec = vec![BigUint::from(0 as usize); num_witness];
-        for j in i {
-            if let Some(element) = new_vec.get_mut(j.1 - 1) {
-                *element = j.0.into();  // Store the BigUint
-            }
+// Benchmarks the circuit of the config over each visited curve
+struct RunOnCurve<'a>(&'a RunConfig);
+
+// Benchmarks each visited circuit over the pairing engine `E`
+struct RunOnCircuit<'a, E>(&'a RunConfig, std::marker::PhantomData<E>);
+
+// Benchmarks the circuit of the config with the inner product argument over each visited group
+struct RunOnGroup<'a>(&'a RunConfig);
+
+// Benchmarks each visited circuit with the inner product argument over the group `G`
+struct RunIpaOnCircuit<'a, G>(&'a RunConfig, std::marker::PhantomData<G>);
+
+impl CurveVisitor for RunOnCurve<'_> {
+    type Output = Option<RunResult>;
+
+    fn visit<E: PairingEngine>(self) -> Self::Output {
+        registry::visit_circuit(&self.0.circuit, RunOnCircuit::<E>(self.0, Default::default()))
+    }
+}
+
+impl<E: PairingEngine> CircuitVisitor<E::Fr> for RunOnCircuit<'_, E> {
+    type Output = RunResult;
+
+    fn visit<C: BenchCircuit<E::Fr>>(self, params: C::Params) -> Self::Output {
+        match self.0.backend {
+            Backend::Marlin => match self.0.pc {
+                PcScheme::MarlinKzg10 => bench_marlin::<E::Fr, C, MarlinKZG10<E, DensePolynomial<E::Fr>>>(self.0, &params),
+                PcScheme::SonicKzg10 => bench_marlin::<E::Fr, C, SonicKZG10<E, DensePolynomial<E::Fr>>>(self.0, &params),
+                // `run` sends it to the groups instead
+                PcScheme::Ipa => unreachable!("The inner product argument runs over the groups"),
+            },
+            Backend::Groth16 => groth16::bench::<E, C>(self.0, &params),
         }
-        new_matrix.push(new_vec);
     }
+}
+
+impl GroupVisitor for RunOnGroup<'_> {
+    type Output = Option<RunResult>;
+
+    fn visit<G: AffineCurve>(self) -> Self::Output {
+        registry::visit_circuit(&self.0.circuit, RunIpaOnCircuit::<G>(self.0, Default::default()))
+    }
+}
+
+impl<G: AffineCurve> CircuitVisitor<G::ScalarField> for RunIpaOnCircuit<'_, G> {
+    type Output = RunResult;
 
-    new_matrix
+    fn visit<C: BenchCircuit<G::ScalarField>>(self, params: C::Params) -> Self::Output {
+        bench_marlin::<G::ScalarField, C, InnerProductArgPC<G, Blake2s, DensePolynomial<G::ScalarField>>>(self.0, &params)
+    }
 }
 
-macro_rules! bench {
-    ($circuit:ident, $field:ty, $pairing_engine:ty, $rounds:expr) => {
-
-        print_info!(
-            "Benchmarking {} {}, rounds: {}",
-            stringify!($circuit),
-            stringify!($pairing_engine),
-            $rounds
-        );
-        
-        let rng = &mut ark_std::test_rng();
-        let c = $circuit::<$field>::new_random(rng, $rounds);
-
-        // Generate the constraint system without optimizations
-        let cs = ConstraintSystem::<$field>::new_ref();
-        cs.set_optimization_goal(OptimizationGoal::None);
-
-        // Show the number of constraints
-        cs.finalize();
-        let _ = c.clone().generate_constraints(cs.clone());
-        print_info!("Constraints: {}", cs.num_constraints());
-        print_info!("Variables: {}", cs.num_constraints());
-
-        // Get the matrices
-        let matrices = cs.to_matrices().unwrap();
-        print_info!("Num witness variables: {}",
-            cs.num_witness_variables() + 1
-        );
-        print_info!(
-            "R1CS non-zeros -  A: {}, B: {}, C: {}", 
-            matrices.a_num_non_zero,
-            matrices.b_num_non_zero,
-            matrices.c_num_non_zero,
-        );
-        let matrix_num_values = cs.num_constraints() * (cs.num_witness_variables() + 1);
-        print_info!(
-            "R1CS zeros -  A: {}, B: {}, C: {}", 
-            matrix_num_values - matrices.a_num_non_zero,
-            matrix_num_values - matrices.b_num_non_zero,
-            matrix_num_values - matrices.c_num_non_zero,
-        );
-        print_info!(
-            "R1CS sparsity -  A: {}%, B: {}%, C: {}%", 
-            ((matrix_num_values - matrices.a_num_non_zero) * 100) / matrix_num_values,
-            (matrix_num_values - matrices.b_num_non_zero) * 100 / matrix_num_values,
-            (matrix_num_values - matrices.c_num_non_zero) * 100 / matrix_num_values,
-        );
-
-        /*print_info!("A: {:?}",
-            prettify_matrix(cs.num_witness_variables() + 1, matrices.a)
-        );
-        print_info!("B: {:?}",
-            prettify_matrix(cs.num_witness_variables() + 1, matrices.b)
-        );
-        print_info!("C: {:?}",
-            prettify_matrix(cs.num_witness_variables() + 1, matrices.c)
-        );*/
-
-        let num_non_zeros = matrices.a_num_non_zero.max(matrices.b_num_non_zero);
-        // Generate the SRS
-        let srs = Marlin::<$field, MarlinKZG10<$pairing_engine, DensePolynomial<$field>>, Blake2s>
-            ::universal_setup(cs.num_constraints(), cs.num_witness_variables() + 1,  num_non_zeros, rng)
-            .unwrap();
-
-        // Generate the setup
-        let start = std::time::Instant::now();
-        let (pk, vk) = Marlin::<$field, MarlinKZG10<$pairing_engine, DensePolynomial<$field>>, Blake2s>
-            ::index(&srs, c.clone())
-            .unwrap();
-        print_info!(
-            "Indexer time: {}s", 
-            start.elapsed().as_millis() as f64 / 1000 as f64
-        );
-
-        // Generate the proof
-        let start = std::time::Instant::now();
-        let proof = Marlin::<$field, MarlinKZG10<$pairing_engine, DensePolynomial<$field>>, Blake2s>
-            ::prove(&pk, c.clone(), rng)
-            .unwrap();
-        print_info!(
-            "Prover time: {}s", 
-            start.elapsed().as_millis() as f64 / 1000 as f64
-        );
-
-        // Check the proof
-        let start = std::time::Instant::now();
-        let res = Marlin::<$field, MarlinKZG10<$pairing_engine, DensePolynomial<$field>>, Blake2s>
-            ::verify(&vk, &[c.get_result()], &proof, rng)
-            .unwrap();
-        print_info!(
-            "Verifier time: {}s", 
-            start.elapsed().as_millis() as f64 / 1000 as f64
-        );
-        print_info!("Verification: {}", res);
-    };
+// Benchmarks Marlin with the commitment scheme `P` and the transcript of the config
+fn bench_marlin<F: PrimeField, C: BenchCircuit<F>, P: BenchPC<F>>(config: &RunConfig, params: &C::Params) -> RunResult {
+    match config.fs {
+        FsScheme::Blake2s => bench::bench::<F, C, P, Blake2sTranscript>(config, params),
+        FsScheme::Blake2b => bench::bench::<F, C, P, Blake2bTranscript>(config, params),
+        FsScheme::Sha256 => bench::bench::<F, C, P, Sha256Transcript>(config, params),
+        FsScheme::Keccak256 => bench::bench::<F, C, P, Keccak256Transcript>(config, params),
+        FsScheme::Poseidon => bench::bench::<F, C, P, PoseidonTranscript<F>>(config, params),
+    }
+}
+
+fn run(config: &RunConfig) -> RunResult {
+    if config.rounds == 0 {
+        print_panic("0 is not a valid number of rounds")
+    }
+    if config.iterations == 0 {
+        print_panic("At least one iteration is needed")
+    }
+    if config.backend != Backend::Marlin && (config.soundness_check || config.batch > 0) {
+        print_panic("--soundness-check and --batch are only available for Marlin")
+    }
+    if config.soundness_check && file_circuit::is_file(&config.circuit) {
+        print_panic("--soundness-check needs a second instance of the circuit, which a circuit file does not have")
+    }
+    if config.pc != PcScheme::MarlinKzg10 && config.batch > 0 {
+        print_panic("--batch is only available with --pc marlin_kzg10")
+    }
+    if config.backend == Backend::Marlin && !config.fs.supports(&config.curve) {
+        print_panic!("--fs poseidon is only available on {}", transcript::POSEIDON_CURVES.join(" and "))
+    }
+
+    if config.memory {
+        memory::enable();
+    }
+
+    let ipa = config.backend == Backend::Marlin && config.pc == PcScheme::Ipa;
+    if ipa != registry::is_group(&config.curve) {
+        print_panic("--pc ipa runs over the groups without pairing and the other schemes over the curves, see --list-curves")
+    }
+
+    threads::install(config.threads, || match ipa {
+        true => registry::visit_group(&config.curve, RunOnGroup(config)),
+        false => registry::visit_curve(&config.curve, RunOnCurve(config)),
+    })
+    .flatten()
+    .unwrap_or_else(|| print_panic!("Invalid circuit {} or curve {}", config.circuit, config.curve))
+}
+
+// Options of a single run given with values other than their defaults
+fn run_options(args: &Args) -> Vec<&'static str> {
+    [
+        (args.system != "basic", "--system"),
+        (args.rounds != 1, "--rounds"),
+        (args.curve != "bls12_381", "--curve"),
+        (args.backend != Backend::default(), "--backend"),
+        (args.pc != PcScheme::default(), "--pc"),
+        (args.fs != FsScheme::default(), "--fs"),
+        (args.public_inputs != 0, "--public-inputs"),
+        (args.warmup != 0, "--warmup"),
+        (args.iterations != 1, "--iterations"),
+        (args.srs_dir.is_some(), "--srs-dir"),
+        (args.srs_max_degree != 0, "--srs-max-degree"),
+        (args.seed != 0, "--seed"),
+        (args.check_reproducible, "--check-reproducible"),
+        (args.threads != 0, "--threads"),
+        (args.scaling, "--scaling"),
+        (args.memory, "--memory"),
+        (args.soundness_check, "--soundness-check"),
+        (args.batch != 0, "--batch"),
+    ]
+    .into_iter()
+    .filter_map(|(set, option)| set.then_some(option))
+    .collect()
 }
 
 fn main() {
     // Accessing command-line arguments
     let args = Args::parse();
 
-    // Get the circuit
-    let circuit_name = args.system.as_str();
-
-    // Get the number of rounds
-    let rounds = args.rounds;
-    if rounds == 0 {
-        print_panic("0 is not a valid number of rounds")
+    if args.list_circuits || args.list_curves {
+        if args.list_circuits {
+            registry::print_entries(CIRCUITS);
+        }
+        if args.list_curves {
+            registry::print_entries(CURVES);
+            registry::print_entries(GROUPS);
+        }
+        return;
     }
 
-    // Get the curve
-    let curve_name = args.curve.as_str();
-
-    match (circuit_name, curve_name) {
-        ("basic", "bls12_381") => {bench!(BasicCircuit, Bls381Fr, Bls12_381, rounds);},
-        ("basic", "bls12_377") => {bench!(BasicCircuit, Bls377Fr, Bls12_377, rounds);},
-        ("basic", "mnt4_298") => {bench!(BasicCircuit, MNT4Fr, MNT4_298, rounds);},
-        ("basic", "mnt4_753") => {bench!(BasicCircuit, MNT4BigFr, MNT4_753, rounds);},
-        ("basic", "mnt6_298") => {bench!(BasicCircuit, MNT6Fr, MNT6_298, rounds);},
-        ("basic", "mnt6_753") => {bench!(BasicCircuit, MNT6BigFr, MNT6_753, rounds);},
-
-        ("product", "bls12_381") => {bench!(ProductCircuit, Bls381Fr, Bls12_381, rounds);},
-        ("product", "bls12_377") => {bench!(ProductCircuit, Bls377Fr, Bls12_377, rounds);},
-        ("product", "mnt4_298") => {bench!(ProductCircuit, MNT4Fr, MNT4_298, rounds);},
-        ("product", "mnt4_753") => {bench!(ProductCircuit, MNT4BigFr, MNT4_753, rounds);},
-        ("product", "mnt6_298") => {bench!(ProductCircuit, MNT6Fr, MNT6_298, rounds);},
-        ("product", "mnt6_753") => {bench!(ProductCircuit, MNT6BigFr, MNT6_753, rounds);},
-
-        ("addition", "bls12_381") => {bench!(AdditionCircuit, Bls381Fr, Bls12_381, rounds);},
-        ("addition", "bls12_377") => {bench!(AdditionCircuit, Bls377Fr, Bls12_377, rounds);},
-        ("addition", "mnt4_298") => {bench!(AdditionCircuit, MNT4Fr, MNT4_298, rounds);},
-        ("addition", "mnt4_753") => {bench!(AdditionCircuit, MNT4BigFr, MNT4_753, rounds);},
-        ("addition", "mnt6_298") => {bench!(AdditionCircuit, MNT6Fr, MNT6_298, rounds);},
-        ("addition", "mnt6_753") => {bench!(AdditionCircuit, MNT6BigFr, MNT6_753, rounds);},
-
-        ("dense", "bls12_381") => {bench!(DenseCircuit, Bls381Fr, Bls12_381, rounds);},
-        ("dense", "bls12_377") => {bench!(DenseCircuit, Bls377Fr, Bls12_377, rounds);},
-        ("dense", "mnt4_298") => {bench!(DenseCircuit, MNT4Fr, MNT4_298, rounds);},
-        ("dense", "mnt4_753") => {bench!(DenseCircuit, MNT4BigFr, MNT4_753, rounds);},
-        ("dense", "mnt6_298") => {bench!(DenseCircuit, MNT6Fr, MNT6_298, rounds);},
-        ("dense", "mnt6_753") => {bench!(DenseCircuit, MNT6BigFr, MNT6_753, rounds);},
-
-        ("fibonacci", "bls12_381") => {bench!(FibonacciCircuit, Bls381Fr, Bls12_381, rounds);},
-        ("fibonacci", "bls12_377") => {bench!(FibonacciCircuit, Bls377Fr, Bls12_377, rounds);},
-        ("fibonacci", "mnt4_298") => {bench!(FibonacciCircuit, MNT4Fr, MNT4_298, rounds);},
-        ("fibonacci", "mnt4_753") => {bench!(FibonacciCircuit, MNT4BigFr, MNT4_753, rounds);},
-        ("fibonacci", "mnt6_298") => {bench!(FibonacciCircuit, MNT6Fr, MNT6_298, rounds);},
-        ("fibonacci", "mnt6_753") => {bench!(FibonacciCircuit, MNT6BigFr, MNT6_753, rounds);},
-
-        ("fibonacci2", "bls12_381") => {bench!(Fibonacci2Circuit, Bls381Fr, Bls12_381, rounds);},
-        ("fibonacci2", "bls12_377") => {bench!(Fibonacci2Circuit, Bls377Fr, Bls12_377, rounds);},
-        ("fibonacci2", "mnt4_298") => {bench!(Fibonacci2Circuit, MNT4Fr, MNT4_298, rounds);},
-        ("fibonacci2", "mnt4_753") => {bench!(Fibonacci2Circuit, MNT4BigFr, MNT4_753, rounds);},
-        ("fibonacci2", "mnt6_298") => {bench!(Fibonacci2Circuit, MNT6Fr, MNT6_298, rounds);},
-        ("fibonacci2", "mnt6_753") => {bench!(Fibonacci2Circuit, MNT6BigFr, MNT6_753, rounds);},
-
-        ("fibonacci3", "bls12_381") => {bench!(Fibonacci3Circuit, Bls381Fr, Bls12_381, rounds);},
-        ("fibonacci3", "bls12_377") => {bench!(Fibonacci3Circuit, Bls377Fr, Bls12_377, rounds);},
-        ("fibonacci3", "mnt4_298") => {bench!(Fibonacci3Circuit, MNT4Fr, MNT4_298, rounds);},
-        ("fibonacci3", "mnt4_753") => {bench!(Fibonacci3Circuit, MNT4BigFr, MNT4_753, rounds);},
-        ("fibonacci3", "mnt6_298") => {bench!(Fibonacci3Circuit, MNT6Fr, MNT6_298, rounds);},
-        ("fibonacci3", "mnt6_753") => {bench!(Fibonacci3Circuit, MNT6BigFr, MNT6_753, rounds);},
-
-        ("sum", "bls12_381") => {bench!(SumCircuit, Bls381Fr, Bls12_381, rounds);},
-        ("sum", "bls12_377") => {bench!(SumCircuit, Bls377Fr, Bls12_377, rounds);},
-        ("sum", "mnt4_298") => {bench!(SumCircuit, MNT4Fr, MNT4_298, rounds);},
-        ("sum", "mnt4_753") => {bench!(SumCircuit, MNT4BigFr, MNT4_753, rounds);},
-        ("sum", "mnt6_298") => {bench!(SumCircuit, MNT6Fr, MNT6_298, rounds);},
-        ("sum", "mnt6_753") => {bench!(SumCircuit, MNT6BigFr, MNT6_753, rounds);},
-
-        ("sumprod", "bls12_381") => {bench!(SumProdCircuit, Bls381Fr, Bls12_381, rounds);},
-        ("sumprod", "bls12_377") => {bench!(SumProdCircuit, Bls377Fr, Bls12_377, rounds);},
-        ("sumprod", "mnt4_298") => {bench!(SumProdCircuit, MNT4Fr, MNT4_298, rounds);},
-        ("sumprod", "mnt4_753") => {bench!(SumProdCircuit, MNT4BigFr, MNT4_753, rounds);},
-        ("sumprod", "mnt6_298") => {bench!(SumProdCircuit, MNT6Fr, MNT6_298, rounds);},
-        ("sumprod", "mnt6_753") => {bench!(SumProdCircuit, MNT6BigFr, MNT6_753, rounds);},
-
-        _ => print_panic!("Invalid circuit {} or curve {}", circuit_name, curve_name)
+    // The subcommands take their own options, after their name
+    let ignored = run_options(&args);
+    if args.command.is_some() && !ignored.is_empty() {
+        print_panic!("{} cannot be given before a subcommand, which takes its own options", ignored.join(", "))
     }
 
+    match &args.command {
+        Some(Command::Sweep(sweep_args)) => {
+            if !sweep::sweep(sweep_args, run) {
+                std::process::exit(1);
+            }
+        }
+        Some(Command::Universal(universal_args)) => {
+            let result = universal::universal(universal_args);
+            result.print(args.output);
+            if !result.circuits.iter().all(|c| c.verified) {
+                std::process::exit(1);
+            }
+        }
+        Some(Command::Export(export_args)) => export::export(export_args),
+        Some(Command::Step(step)) => steps::run(step),
+        None => {
+            let config = RunConfig {
+                backend: args.backend,
+                pc: args.pc,
+                fs: args.fs,
+                circuit: args.system.clone(),
+                curve: args.curve.clone(),
+                rounds: args.rounds,
+                public_inputs: args.public_inputs,
+                seed: args.seed,
+                prover_variant: 0,
+                warmup: args.warmup,
+                iterations: args.iterations,
+                threads: args.threads,
+                memory: args.memory,
+                soundness_check: args.soundness_check,
+                batch: args.batch,
+                srs_dir: args.srs_dir.clone(),
+                srs_max_degree: args.srs_max_degree,
+            };
+            if args.scaling {
+                scaling::scaling(&config, run).print(args.output);
+            } else if args.check_reproducible {
+                let result = reproducible::check(&config, run);
+                result.print(args.output);
+                if !result.reproducible {
+                    std::process::exit(1);
+                }
+            } else {
+                let result = run(&config);
+                result.print(args.output);
+                if !result.passed() {
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,154 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+/// System allocator that keeps track of the heap usage once `enable` is
+/// called. Until then it only checks a flag, so that the counters shared by
+/// every thread do not slow down the timed phases.
+pub struct CountingAllocator;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+impl CountingAllocator {
+    fn record_alloc(size: usize) {
+        ALLOCATED.fetch_add(size, Ordering::Relaxed);
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn record_dealloc(size: usize) {
+        // Memory allocated before counting started is freed without having been counted
+        let _ = CURRENT.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| Some(current.saturating_sub(size)));
+    }
+}
+
+/// Starts counting the allocations, for the rest of the process
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() && enabled() {
+            Self::record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() && enabled() {
+            Self::record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        if enabled() {
+            Self::record_dealloc(layout.size());
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() && enabled() {
+            Self::record_dealloc(layout.size());
+            Self::record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Memory used while running one phase
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct MemoryUsage {
+    // Bytes requested to the allocator, freed or not
+    pub allocated_bytes: usize,
+    pub allocations: usize,
+    // Highest live heap reached during the phase
+    pub peak_heap_bytes: usize,
+    // Highest resident set size of the process (VmHWM), None outside Linux
+    pub peak_rss_bytes: Option<usize>,
+}
+
+// Reads a "<field>: <value> kB" line of /proc/self/status
+fn proc_status_bytes(field: &str) -> Option<usize> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with(field))?;
+    let kb: usize = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+/// Runs `f` and reports the memory it used, nothing unless counting is enabled
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, MemoryUsage) {
+    if !enabled() {
+        return (f(), MemoryUsage::default());
+    }
+
+    // Reset the peak RSS of the process, only possible on Linux
+    let _ = fs::write("/proc/self/clear_refs", "5");
+
+    let allocated = ALLOCATED.load(Ordering::Relaxed);
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let current = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(current, Ordering::Relaxed);
+
+    let result = f();
+
+    let usage = MemoryUsage {
+        allocated_bytes: ALLOCATED.load(Ordering::Relaxed) - allocated,
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        peak_heap_bytes: PEAK.load(Ordering::Relaxed).saturating_sub(current),
+        peak_rss_bytes: proc_status_bytes("VmHWM:"),
+    };
+    (result, usage)
+}
+
+/// Memory used by each phase of a run
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MemoryPhases {
+    pub setup: MemoryUsage,
+    pub synthesis: MemoryUsage,
+    pub index: MemoryUsage,
+    pub prove: MemoryUsage,
+    pub verify: MemoryUsage,
+}
+
+impl MemoryPhases {
+    pub fn summaries(&self) -> Vec<(&'static str, MemoryUsage)> {
+        vec![
+            ("setup", self.setup),
+            ("synthesis", self.synthesis),
+            ("index", self.index),
+            ("prove", self.prove),
+            ("verify", self.verify),
+        ]
+    }
+}
+
+/// Formats a number of bytes with the most readable unit
+pub fn format_bytes(bytes: usize) -> String {
+    let bytes = bytes as f64;
+    if bytes >= 1024.0 * 1024.0 * 1024.0 {
+        format!("{:.2}GiB", bytes / (1024.0 * 1024.0 * 1024.0))
+    } else if bytes >= 1024.0 * 1024.0 {
+        format!("{:.2}MiB", bytes / (1024.0 * 1024.0))
+    } else if bytes >= 1024.0 {
+        format!("{:.2}KiB", bytes / 1024.0)
+    } else {
+        format!("{}B", bytes)
+    }
+}
@@ -0,0 +1,124 @@
+use ark_ec::{AffineCurve, PairingEngine};
+use ark_ff::PrimeField;
+use ark_marlin::{IndexProverKey, IndexVerifierKey};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly_commit::ipa_pc::{self, InnerProductArgPC};
+use ark_poly_commit::kzg10;
+use ark_poly_commit::marlin_pc::MarlinKZG10;
+use ark_poly_commit::sonic_pc::SonicKZG10;
+use ark_poly_commit::PolynomialCommitment;
+use blake2::Blake2s;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::batch::{self, BatchVerification};
+use crate::circuit_traits::BenchCircuit;
+use crate::transcript::Transcript;
+use crate::RunConfig;
+
+/// Polynomial commitment scheme Marlin is instantiated with
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[value(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PcScheme {
+    /// KZG10 with degree bounds enforced by shifted commitments
+    #[default]
+    MarlinKzg10,
+    /// KZG10 with degree bounds enforced by extra G2 powers in the verifier key
+    SonicKzg10,
+    /// Inner product argument over a group without pairing, with a transparent setup
+    Ipa,
+}
+
+impl PcScheme {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PcScheme::MarlinKzg10 => "marlin_kzg10",
+            PcScheme::SonicKzg10 => "sonic_kzg10",
+            PcScheme::Ipa => "ipa",
+        }
+    }
+
+    /// Name under which the SRS of the curve is stored. The SRS of each scheme
+    /// has different powers, and MarlinKZG10 keeps the names it had before
+    /// there was a choice.
+    pub fn srs_name(&self, curve: &str) -> String {
+        match self {
+            PcScheme::MarlinKzg10 => curve.to_string(),
+            scheme => format!("{}-{}", curve, scheme.name()),
+        }
+    }
+}
+
+/// Commitment scheme over the field `F` that the benchmark can instantiate
+/// Marlin with
+pub trait BenchPC<F: PrimeField>: PolynomialCommitment<F, DensePolynomial<F>> {
+    const SCHEME: PcScheme;
+
+    /// Part of `srs` that supports `max_degree`, with the size of the SRS that
+    /// setup generates for that degree, so that a bigger stored SRS reports
+    /// the same as an exact one
+    fn truncate(srs: &Self::UniversalParams, max_degree: usize) -> Self::UniversalParams;
+
+    /// Separate and batched verification of `config.batch` proofs, which is
+    /// only implemented for MarlinKZG10
+    fn batch<C: BenchCircuit<F>, FS: Transcript>(
+        _config: &RunConfig,
+        _params: &C::Params,
+        _pk: &IndexProverKey<F, Self>,
+        _vk: &IndexVerifierKey<F, Self>,
+    ) -> Option<BatchVerification> {
+        None
+    }
+}
+
+// The powers of the secret up to `max_degree`, which is exactly the SRS setup
+// generates for that degree from the same RNG
+fn truncate_kzg<E: PairingEngine>(srs: &kzg10::UniversalParams<E>, max_degree: usize) -> kzg10::UniversalParams<E> {
+    let mut srs = srs.clone();
+    srs.powers_of_g.truncate(max_degree + 1);
+    // One more power of gamma * G supports max_degree queries
+    srs.powers_of_gamma_g.retain(|i, _| *i <= max_degree + 1);
+    srs.neg_powers_of_h.retain(|i, _| *i <= max_degree);
+    srs
+}
+
+impl<E: PairingEngine> BenchPC<E::Fr> for MarlinKZG10<E, DensePolynomial<E::Fr>> {
+    const SCHEME: PcScheme = PcScheme::MarlinKzg10;
+
+    fn truncate(srs: &Self::UniversalParams, max_degree: usize) -> Self::UniversalParams {
+        truncate_kzg(srs, max_degree)
+    }
+
+    fn batch<C: BenchCircuit<E::Fr>, FS: Transcript>(
+        config: &RunConfig,
+        params: &C::Params,
+        pk: &IndexProverKey<E::Fr, Self>,
+        vk: &IndexVerifierKey<E::Fr, Self>,
+    ) -> Option<BatchVerification> {
+        Some(batch::measure::<E, C, FS>(config, params, pk, vk))
+    }
+}
+
+impl<E: PairingEngine> BenchPC<E::Fr> for SonicKZG10<E, DensePolynomial<E::Fr>> {
+    const SCHEME: PcScheme = PcScheme::SonicKzg10;
+
+    fn truncate(srs: &Self::UniversalParams, max_degree: usize) -> Self::UniversalParams {
+        truncate_kzg(srs, max_degree)
+    }
+}
+
+// Only needs a prime-order group, such as the ones of the Pasta curves
+impl<G: AffineCurve> BenchPC<G::ScalarField> for InnerProductArgPC<G, Blake2s, DensePolynomial<G::ScalarField>> {
+    const SCHEME: PcScheme = PcScheme::Ipa;
+
+    // Setup samples a power of two generators, and its last two become h and
+    // s, so only the size matches the one of an exact setup
+    fn truncate(srs: &Self::UniversalParams, max_degree: usize) -> Self::UniversalParams {
+        ipa_pc::UniversalParams {
+            comm_key: srs.comm_key[..(max_degree + 1).next_power_of_two()].to_vec(),
+            h: srs.h,
+            s: srs.s,
+        }
+    }
+}
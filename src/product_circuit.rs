@@ -15,19 +15,21 @@ pub struct ProductCircuit<F: PrimeField> {
 
 // Constructor for ProductCircuit
 impl<F: PrimeField> BenchCircuit<F> for ProductCircuit<F> {
-    fn new_random<R: RngCore>(rng: &mut R, rounds: usize) -> Self {
+    type Params = ();
+
+    fn new_random<R: RngCore>(_params: &(), rng: &mut R, rounds: usize) -> Self {
         ProductCircuit { 
             x: <F>::rand(rng),  
             t: rounds + 3 
         }
     }
 
-    fn get_result(&self) -> F {
+    fn public_inputs(&self) -> Vec<F> {
         let mut r = self.x;
         for _ in 0..(self.t - 1) {
-            r = r * self.x;
+            r *= self.x;
         }
-        r
+        vec![r]
     }
 } 
 
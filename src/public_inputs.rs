@@ -0,0 +1,87 @@
+//! Extra public inputs for any circuit, to measure how the verifier, the proof
+//! and the |X| domain of Marlin grow with the size of the instance.
+
+use ark_ff::PrimeField;
+use ark_relations::{
+    lc,
+    r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable},
+};
+use ark_std::rand::RngCore;
+
+use crate::circuit_traits::BenchCircuit;
+
+/// The circuit `C` followed by random public inputs, each one bound to a
+/// witness variable by the constraint `input * 1 = witness`. Without extra
+/// inputs it has the same constraints and randomness as `C`.
+#[derive(Clone)]
+pub struct WithPublicInputs<F, C> {
+    circuit: C,
+    extra: Vec<F>,
+}
+
+impl<F: PrimeField, C: BenchCircuit<F>> WithPublicInputs<F, C> {
+    /// Adds `extra_inputs` public inputs drawn from `rng` to the circuit
+    pub fn new<R: RngCore>(circuit: C, extra_inputs: usize, rng: &mut R) -> Self {
+        let extra = (0..extra_inputs).map(|_| F::rand(rng)).collect();
+        WithPublicInputs { circuit, extra }
+    }
+
+    /// Public inputs of the circuit followed by the extra ones
+    pub fn public_inputs(&self) -> Vec<F> {
+        [self.circuit.public_inputs(), self.extra.clone()].concat()
+    }
+}
+
+impl<F: PrimeField, C: BenchCircuit<F>> ConstraintSynthesizer<F> for WithPublicInputs<F, C> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        self.circuit.generate_constraints(cs.clone())?;
+
+        for value in self.extra {
+            let input = cs.new_input_variable(|| Ok(value))?;
+            let witness = cs.new_witness_variable(|| Ok(value))?;
+            cs.enforce_constraint(lc!() + input, lc!() + Variable::One, lc!() + witness)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Fr;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+
+    use super::WithPublicInputs;
+    use crate::circuit_traits::BenchCircuit;
+    use crate::registry::{self, CircuitVisitor, CIRCUITS};
+    use crate::seed::{self, Stream};
+
+    struct CheckCircuit<'a>(&'a str);
+
+    impl CircuitVisitor<Fr> for CheckCircuit<'_> {
+        type Output = ();
+
+        // `public_inputs` must list the instance the circuit assigns, in its order
+        fn visit<C: BenchCircuit<Fr>>(self, params: C::Params) {
+            let rng = &mut seed::rng(0, Stream::Circuit);
+            for extra_inputs in [0, 3] {
+                let circuit = WithPublicInputs::new(C::new_random(&params, rng, 5), extra_inputs, rng);
+                let inputs = circuit.public_inputs();
+
+                let cs = ConstraintSystem::<Fr>::new_ref();
+                circuit.generate_constraints(cs.clone()).unwrap();
+                assert!(cs.is_satisfied().unwrap(), "{} with {} extra inputs", self.0, extra_inputs);
+                // The first instance variable is the constant one
+                let instance = cs.borrow().unwrap().instance_assignment[1..].to_vec();
+                assert_eq!(inputs, instance, "{} with {} extra inputs", self.0, extra_inputs);
+            }
+        }
+    }
+
+    #[test]
+    fn public_inputs_match_the_instance() {
+        for circuit in CIRCUITS {
+            registry::visit_circuit(circuit.name, CheckCircuit(circuit.name)).unwrap();
+        }
+    }
+}
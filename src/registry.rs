@@ -0,0 +1,132 @@
+use ark_ec::{AffineCurve, PairingEngine};
+use ark_ff::PrimeField;
+
+use crate::circuit_traits::BenchCircuit;
+use crate::basic_circuit::BasicCircuit;
+use crate::addition_circuit::AdditionCircuit;
+use crate::product_circuit::ProductCircuit;
+use crate::dense_circuit::DenseCircuit;
+use crate::fibonacci_circuit::FibonacciCircuit;
+use crate::fibonacci2_circuit::Fibonacci2Circuit;
+use crate::fibonacci3_circuit::Fibonacci3Circuit;
+use crate::sum_circuit::SumCircuit;
+use crate::sumprod_circuit::SumProdCircuit;
+use crate::file_circuit::{self, FileCircuit};
+
+/// Name and description of a registered circuit, curve or group
+pub struct Entry {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+/// Operation that is generic over the circuit type
+pub trait CircuitVisitor<F: PrimeField> {
+    type Output;
+    fn visit<C: BenchCircuit<F>>(self, params: C::Params) -> Self::Output;
+}
+
+/// Operation that is generic over the pairing engine
+pub trait CurveVisitor {
+    type Output;
+    fn visit<E: PairingEngine>(self) -> Self::Output;
+}
+
+/// Operation that is generic over a prime-order group without pairing
+pub trait GroupVisitor {
+    type Output;
+    fn visit<G: AffineCurve>(self) -> Self::Output;
+}
+
+macro_rules! register_circuits {
+    ($($name:literal => $circuit:ident, $description:literal;)*) => {
+        pub const CIRCUITS: &[Entry] = &[
+            $(Entry { name: $name, description: $description },)*
+        ];
+
+        /// Calls the visitor with the circuit registered as `name` and what it
+        /// is built from
+        pub fn visit_circuit<F: PrimeField, V: CircuitVisitor<F>>(name: &str, visitor: V) -> Option<V::Output> {
+            match name {
+                $($name => Some(visitor.visit::<$circuit<F>>(())),)*
+                // Circuits compiled by circom are named by their path
+                path if file_circuit::is_file(path) => {
+                    Some(visitor.visit::<FileCircuit<F>>(file_circuit::read(path)))
+                }
+                _ => None,
+            }
+        }
+    };
+}
+
+macro_rules! register_curves {
+    ($($name:literal => $engine:ty, $description:literal;)*) => {
+        pub const CURVES: &[Entry] = &[
+            $(Entry { name: $name, description: $description },)*
+        ];
+
+        /// Calls the visitor with the pairing engine registered as `name`
+        pub fn visit_curve<V: CurveVisitor>(name: &str, visitor: V) -> Option<V::Output> {
+            match name {
+                $($name => Some(visitor.visit::<$engine>()),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+macro_rules! register_groups {
+    ($($name:literal => $group:ty, $description:literal;)*) => {
+        pub const GROUPS: &[Entry] = &[
+            $(Entry { name: $name, description: $description },)*
+        ];
+
+        /// Calls the visitor with the group registered as `name`
+        pub fn visit_group<V: GroupVisitor>(name: &str, visitor: V) -> Option<V::Output> {
+            match name {
+                $($name => Some(visitor.visit::<$group>()),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+register_circuits! {
+    "basic" => BasicCircuit, "a * b = c repeated, with c public";
+    "product" => ProductCircuit, "x^t computed with one multiplication per constraint";
+    "addition" => AdditionCircuit, "(a + s) * b = c repeated, with c public";
+    "dense" => DenseCircuit, "(a + b) * (a + b) = c repeated, with c public";
+    "fibonacci" => FibonacciCircuit, "Fibonacci sequence with the additions in matrix A";
+    "fibonacci2" => Fibonacci2Circuit, "Fibonacci sequence with the additions in matrix B";
+    "fibonacci3" => Fibonacci3Circuit, "Fibonacci sequence alternating the additions between A and B";
+    "sum" => SumCircuit, "Every value is the sum of all the previous ones";
+    "sumprod" => SumProdCircuit, "Every value is the square of the sum of all the previous ones";
+}
+
+register_curves! {
+    "bls12_381" => ark_bls12_381::Bls12_381, "BLS12-381, 255-bit scalar field";
+    "bls12_377" => ark_bls12_377::Bls12_377, "BLS12-377, 253-bit scalar field, inner curve for recursion";
+    "bn254" => ark_bn254::Bn254, "BN254 (alt_bn128), 254-bit scalar field, Ethereum precompiles";
+    "mnt4_298" => ark_mnt4_298::MNT4_298, "MNT4-298, cycle with MNT6-298";
+    "mnt4_753" => ark_mnt4_753::MNT4_753, "MNT4-753, cycle with MNT6-753";
+    "mnt6_298" => ark_mnt6_298::MNT6_298, "MNT6-298, cycle with MNT4-298";
+    "mnt6_753" => ark_mnt6_753::MNT6_753, "MNT6-753, cycle with MNT4-753";
+    "bw6_761" => ark_bw6_761::BW6_761, "BW6-761, outer curve of BLS12-377";
+    "cp6_782" => ark_cp6_782::CP6_782, "CP6-782, outer curve of BLS12-377";
+}
+
+// Groups for the inner product argument, which needs no pairing
+register_groups! {
+    "pallas" => ark_pallas::Affine, "Pallas, 255-bit scalar field, cycle with Vesta";
+    "vesta" => ark_vesta::Affine, "Vesta, 255-bit scalar field, cycle with Pallas";
+}
+
+/// Whether `name` is a registered group, which only the inner product argument runs over
+pub fn is_group(name: &str) -> bool {
+    GROUPS.iter().any(|group| group.name == name)
+}
+
+pub fn print_entries(entries: &[Entry]) {
+    for entry in entries {
+        println!("{:<12} {}", entry.name, entry.description);
+    }
+}
@@ -0,0 +1,250 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::batch::BatchVerification;
+use crate::constraint_stats::ConstraintStats;
+use crate::fingerprint::Fingerprints;
+use crate::instrumented::{IndexerPhases, ProverPhases, VerifierPhases};
+use crate::memory::{format_bytes, MemoryPhases};
+use crate::pc::PcScheme;
+use crate::transcript::FsScheme;
+use crate::print_info;
+use crate::sizes::ArtifactSizes;
+use crate::soundness::SoundnessCase;
+use crate::stats::PhaseStats;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Colored human readable lines
+    Text,
+    /// One JSON record per run
+    Json,
+}
+
+/// Proving system that runs the circuit
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// Marlin with a universal SRS, indexed for each circuit
+    #[default]
+    Marlin,
+    /// Groth16 with a circuit-specific setup
+    Groth16,
+}
+
+impl Backend {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Backend::Marlin => "marlin",
+            Backend::Groth16 => "groth16",
+        }
+    }
+}
+
+/// Everything measured during a single benchmark run
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RunResult {
+    #[serde(default)]
+    pub backend: Backend,
+    // Commitment scheme and transcript of Marlin, none for Groth16
+    #[serde(default)]
+    pub pc: Option<PcScheme>,
+    #[serde(default)]
+    pub fs: Option<FsScheme>,
+    pub circuit: String,
+    pub curve: String,
+    pub rounds: usize,
+    // Extra public inputs added to the circuit
+    #[serde(default)]
+    pub public_inputs: usize,
+    pub seed: u64,
+    pub threads: usize,
+    pub warmup: usize,
+    pub iterations: usize,
+    pub srs_max_degree: usize,
+    // Whether the SRS was loaded from disk instead of generated
+    pub srs_cached: bool,
+
+    pub constraint_system: ConstraintStats,
+
+    // Setup of a generated SRS, or of the circuit with Groth16
+    pub setup: PhaseStats,
+    // Reading a stored SRS instead of generating it
+    #[serde(default)]
+    pub srs_load: PhaseStats,
+    pub indexer: PhaseStats,
+    pub prover: PhaseStats,
+    pub verifier: PhaseStats,
+    pub indexer_phases: IndexerPhases,
+    pub prover_phases: ProverPhases,
+    pub verifier_phases: VerifierPhases,
+    pub sizes: ArtifactSizes,
+    // Only with --memory
+    #[serde(default)]
+    pub memory: Option<MemoryPhases>,
+    pub fingerprints: Fingerprints,
+    // Tampered cases given to the verifier, only with --soundness-check
+    #[serde(default)]
+    pub soundness: Vec<SoundnessCase>,
+    // Separate and batched verification, only with --batch
+    #[serde(default)]
+    pub batch: Option<BatchVerification>,
+    pub verified: bool,
+}
+
+impl RunResult {
+    pub fn print(&self, format: OutputFormat) {
+        match format {
+            OutputFormat::Text => self.print_text(),
+            OutputFormat::Json => self.print_json(),
+        }
+    }
+
+    fn print_text(&self) {
+        print_info!(
+            "Benchmarking {} {} with {}, rounds: {}, extra public inputs: {}, seed: {}, threads: {}",
+            self.circuit,
+            self.curve,
+            self.system(),
+            self.rounds,
+            self.public_inputs,
+            self.seed,
+            self.threads
+        );
+        let cs = &self.constraint_system;
+        print_info!("Constraints: {}", cs.constraints);
+        print_info!(
+            "Variables: {} ({} instance, {} witness)",
+            cs.variables,
+            cs.instance_variables,
+            cs.witness_variables
+        );
+        print_info!("R1CS non-zeros -  A: {}, B: {}, C: {}", cs.a.non_zero, cs.b.non_zero, cs.c.non_zero);
+        print_info!(
+            "R1CS density -  A: {:.2}%, B: {:.2}%, C: {:.2}%",
+            cs.a.density,
+            cs.b.density,
+            cs.c.density
+        );
+        match self.backend {
+            Backend::Marlin => {
+                let domains = [("H", &cs.domain_h), ("K", &cs.domain_k), ("X", &cs.domain_x)];
+                for (name, domain) in domains.into_iter().filter_map(|(name, domain)| Some((name, domain.as_ref()?))) {
+                    print_info!(
+                        "Domain |{}|: {} for {} elements ({:.2}% padding)",
+                        name,
+                        domain.size,
+                        domain.used,
+                        domain.waste
+                    );
+                }
+                // The IPA parameters are sampled by hashing, with no trapdoor
+                let srs = if self.pc == Some(PcScheme::Ipa) { "transparent parameters" } else { "SRS" };
+                match self.srs_cached {
+                    true => print_info!(
+                        "SRS load time: {} (stored {} used up to max degree {})",
+                        self.srs_load.summary(),
+                        srs,
+                        self.srs_max_degree
+                    ),
+                    false => print_info!(
+                        "Setup time: {} (generated {} of max degree {})",
+                        self.setup.summary(),
+                        srs,
+                        self.srs_max_degree
+                    ),
+                }
+                print_info!("Indexer time: {}", self.indexer.summary());
+                for (phase, summary) in self.indexer_phases.summaries() {
+                    print_info!("    {}: {}", phase, summary);
+                }
+                print_info!("Prover time: {}", self.prover.summary());
+                for (phase, summary) in self.prover_phases.summaries() {
+                    print_info!("    {}: {}", phase, summary);
+                }
+                print_info!("Verifier time: {}", self.verifier.summary());
+                for (phase, summary) in self.verifier_phases.summaries() {
+                    print_info!("    {}: {}", phase, summary);
+                }
+            }
+            Backend::Groth16 => {
+                print_info!("Circuit-specific setup time: {}", self.setup.summary());
+                print_info!("Prover time: {}", self.prover.summary());
+                print_info!("Verifier time: {}", self.verifier.summary());
+            }
+        }
+        // Groth16 has no SRS nor a breakdown of the proof
+        for (artifact, size) in self.sizes.summaries().into_iter().filter(|(_, size)| size.uncompressed > 0) {
+            print_info!(
+                "{} size: {} bytes ({} uncompressed)",
+                artifact,
+                size.compressed,
+                size.uncompressed
+            );
+        }
+        let memory = self.memory.as_ref().map_or_else(Vec::new, MemoryPhases::summaries);
+        for (phase, usage) in memory.into_iter().filter(|(_, usage)| usage.allocations > 0) {
+            print_info!(
+                "Memory {}: peak heap {}, allocated {} in {} allocations, peak RSS {}",
+                phase,
+                format_bytes(usage.peak_heap_bytes),
+                format_bytes(usage.allocated_bytes),
+                usage.allocations,
+                usage.peak_rss_bytes.map_or("unknown".to_string(), format_bytes),
+            );
+        }
+        print_info!("Matrices fingerprint: {}", self.fingerprints.matrices);
+        if !self.fingerprints.srs.is_empty() {
+            print_info!("SRS fingerprint: {}", self.fingerprints.srs);
+        }
+        print_info!("Verifying key fingerprint: {}", self.fingerprints.verifying_key);
+        print_info!("Proof fingerprint: {}", self.fingerprints.proof);
+        if !self.soundness.is_empty() {
+            let rejected = self.soundness.iter().filter(|case| case.rejected()).count();
+            print_info!("Soundness: {}/{} tampered cases rejected", rejected, self.soundness.len());
+            for case in self.soundness.iter().filter(|case| !case.rejected()) {
+                print_info!("    accepted: {}", case.case);
+            }
+        }
+        if let Some(batch) = &self.batch {
+            print_info!("Batch verification: {}", batch.summary());
+            print_info!(
+                "Batch verification results - separate: {}, batched: {}, tampered batch rejected: {}",
+                batch.separate_verified,
+                batch.batched_verified,
+                batch.tampered_rejected
+            );
+        }
+        print_info!("Verification: {}", self.verified);
+    }
+
+    /// Proving system of the run, with the commitment scheme and transcript of Marlin
+    pub fn system(&self) -> String {
+        system_name(self.backend, self.pc.zip(self.fs))
+    }
+
+    /// Whether every tampered case of the soundness check was rejected
+    pub fn sound(&self) -> bool {
+        self.soundness.iter().all(|case| case.rejected())
+    }
+
+    /// Whether the proof verified, every tampered case was rejected and the
+    /// batch, if any, behaved as expected
+    pub fn passed(&self) -> bool {
+        self.verified && self.sound() && self.batch.as_ref().is_none_or(BatchVerification::passed)
+    }
+
+    fn print_json(&self) {
+        println!("{}", serde_json::to_string(self).unwrap());
+    }
+}
+
+/// Name of a backend with its commitment scheme and transcript, if it has a
+/// choice of them
+pub fn system_name(backend: Backend, options: Option<(PcScheme, FsScheme)>) -> String {
+    match options {
+        Some((pc, fs)) => format!("{}/{}/{}", backend.name(), pc.name(), fs.name()),
+        None => backend.name().to_string(),
+    }
+}
+
@@ -0,0 +1,84 @@
+use serde::Serialize;
+
+use crate::report::{OutputFormat, RunResult};
+use crate::{print_info, RunConfig};
+
+/// Outcome of running the same configuration several times
+#[derive(Debug, Serialize)]
+pub struct Reproducibility {
+    pub circuit: String,
+    pub curve: String,
+    pub rounds: usize,
+    pub seed: u64,
+    // Two runs with the same seed built the same values
+    pub matrices: bool,
+    pub srs: bool,
+    pub verifying_key: bool,
+    pub proof: bool,
+    // Another zero-knowledge variant only changed the proof, which still verifies
+    pub zk_proof_differs: bool,
+    pub zk_proof_verified: bool,
+    pub reproducible: bool,
+}
+
+/// Runs the config twice with the same seed, and once more changing only the
+/// zero-knowledge randomness of the prover, comparing the fingerprints. The
+/// first run always generates the SRS, so that a stored one is compared with
+/// the SRS its seed gives.
+pub fn check(config: &RunConfig, run: fn(&RunConfig) -> RunResult) -> Reproducibility {
+    let first = run(&RunConfig {
+        srs_dir: None,
+        ..config.clone()
+    });
+    let second = run(config);
+    let zk = run(&RunConfig {
+        prover_variant: config.prover_variant + 1,
+        ..config.clone()
+    });
+
+    let (a, b, z) = (&first.fingerprints, &second.fingerprints, &zk.fingerprints);
+    let matrices = a.matrices == b.matrices && a.matrices == z.matrices;
+    let srs = a.srs == b.srs && a.srs == z.srs;
+    let verifying_key = a.verifying_key == b.verifying_key && a.verifying_key == z.verifying_key;
+    let proof = a.proof == b.proof;
+    let zk_proof_differs = a.proof != z.proof;
+    let zk_proof_verified = first.verified && second.verified && zk.verified;
+
+    Reproducibility {
+        circuit: config.circuit.clone(),
+        curve: config.curve.clone(),
+        rounds: config.rounds,
+        seed: config.seed,
+        matrices,
+        srs,
+        verifying_key,
+        proof,
+        zk_proof_differs,
+        zk_proof_verified,
+        reproducible: matrices && srs && verifying_key && proof && zk_proof_differs && zk_proof_verified,
+    }
+}
+
+impl Reproducibility {
+    pub fn print(&self, format: OutputFormat) {
+        match format {
+            OutputFormat::Text => {
+                print_info!(
+                    "Reproducibility of {} {}, rounds: {}, seed: {}",
+                    self.circuit,
+                    self.curve,
+                    self.rounds,
+                    self.seed
+                );
+                print_info!("Same matrices: {}", self.matrices);
+                print_info!("Same SRS: {}", self.srs);
+                print_info!("Same verifying key: {}", self.verifying_key);
+                print_info!("Same proof: {}", self.proof);
+                print_info!("Different proof with other zero-knowledge randomness: {}", self.zk_proof_differs);
+                print_info!("Every proof verified: {}", self.zk_proof_verified);
+                print_info!("Reproducible: {}", self.reproducible);
+            }
+            OutputFormat::Json => println!("{}", serde_json::to_string(self).unwrap()),
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use serde::Serialize;
+
+use crate::report::{OutputFormat, RunResult};
+use crate::stats::format_ns;
+use crate::{print_info, print_panic, threads, RunConfig};
+
+/// Median time of a phase with a given number of threads, compared to one thread
+#[derive(Debug, Serialize)]
+pub struct PhaseScaling {
+    pub phase: String,
+    pub median_ns: f64,
+    pub speedup: f64,
+    // Speedup divided by the number of threads
+    pub efficiency: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScalingPoint {
+    pub threads: usize,
+    pub phases: Vec<PhaseScaling>,
+}
+
+/// Same circuit and curve run with an increasing number of threads
+#[derive(Debug, Serialize)]
+pub struct Scaling {
+    pub circuit: String,
+    pub curve: String,
+    pub rounds: usize,
+    pub points: Vec<ScalingPoint>,
+    pub runs: Vec<RunResult>,
+}
+
+// 1, 2, 4, ... up to `max`, which is always included
+fn thread_counts(max: usize) -> Vec<usize> {
+    let mut counts: Vec<usize> = std::iter::successors(Some(1usize), |t| Some(t * 2))
+        .take_while(|t| *t < max)
+        .collect();
+    counts.push(max);
+    counts
+}
+
+// Median of the top level phases followed by their breakdowns
+fn phase_medians(result: &RunResult) -> Vec<(String, f64)> {
+    let mut medians = vec![("setup".to_string(), result.setup.median_ns)];
+    let breakdowns = [
+        ("indexer", &result.indexer, result.indexer_phases.stats()),
+        ("prover", &result.prover, result.prover_phases.stats()),
+        ("verifier", &result.verifier, result.verifier_phases.stats()),
+    ];
+    // Groth16 has no indexer nor breakdowns, which are left without samples
+    for (name, total, phases) in breakdowns {
+        if !total.samples_ns.is_empty() {
+            medians.push((name.to_string(), total.median_ns));
+        }
+        for (phase, stats) in phases.into_iter().filter(|(_, stats)| !stats.samples_ns.is_empty()) {
+            medians.push((format!("{}.{}", name, phase), stats.median_ns));
+        }
+    }
+    medians
+}
+
+/// Runs the config with 1, 2, 4, ... threads up to the threads of the config,
+/// always generating the SRS
+pub fn scaling(config: &RunConfig, run: fn(&RunConfig) -> RunResult) -> Scaling {
+    // Without the parallel feature every point would run in the same single thread
+    if !cfg!(feature = "parallel") {
+        print_panic!("--scaling needs more than one thread, but the parallel feature is not enabled")
+    }
+    let max = threads::resolve(config.threads);
+
+    let runs: Vec<RunResult> = thread_counts(max)
+        .into_iter()
+        .map(|threads| {
+            // A stored SRS would only time reading it, not the setup
+            run(&RunConfig {
+                threads,
+                srs_dir: None,
+                ..config.clone()
+            })
+        })
+        .collect();
+
+    let base = phase_medians(&runs[0]);
+    let points = runs
+        .iter()
+        .map(|result| ScalingPoint {
+            threads: result.threads,
+            phases: phase_medians(result)
+                .into_iter()
+                .zip(&base)
+                .map(|((phase, median_ns), (_, base_ns))| {
+                    let speedup = base_ns / median_ns;
+                    PhaseScaling {
+                        phase,
+                        median_ns,
+                        speedup,
+                        efficiency: speedup / result.threads as f64,
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+
+    Scaling {
+        circuit: config.circuit.clone(),
+        curve: config.curve.clone(),
+        rounds: config.rounds,
+        points,
+        runs,
+    }
+}
+
+impl Scaling {
+    pub fn print(&self, format: OutputFormat) {
+        match format {
+            OutputFormat::Text => self.print_text(),
+            OutputFormat::Json => println!("{}", serde_json::to_string(self).unwrap()),
+        }
+    }
+
+    fn print_text(&self) {
+        print_info!(
+            "Scaling of {} {}, rounds: {}",
+            self.circuit,
+            self.curve,
+            self.rounds
+        );
+        let header = format!("{:<24}", "phase");
+        let header = self
+            .points
+            .iter()
+            .fold(header, |header, point| header + &format!(" {:>26}", format!("{} threads", point.threads)));
+        println!("{}", header);
+
+        let num_phases = self.points.first().map_or(0, |point| point.phases.len());
+        for i in 0..num_phases {
+            let mut line = format!("{:<24}", self.points[0].phases[i].phase);
+            for point in &self.points {
+                let phase = &point.phases[i];
+                let cell = format!(
+                    "{} x{:.2} {:.0}%",
+                    format_ns(phase.median_ns),
+                    phase.speedup,
+                    phase.efficiency * 100.0
+                );
+                line += &format!(" {:>26}", cell);
+            }
+            println!("{}", line);
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+/// Independent streams of randomness derived from the same seed, so that
+/// changing how much one phase consumes doesn't shift the others
+#[derive(Clone, Copy, Debug)]
+pub enum Stream {
+    Circuit,
+    Setup,
+    Verifier,
+    // Last, since the following streams are the other zero-knowledge variants
+    Prover,
+}
+
+/// ChaCha20 RNG for one stream of the seed
+pub fn rng(seed: u64, stream: Stream) -> ChaCha20Rng {
+    stream_rng(seed, stream as u64)
+}
+
+/// ChaCha20 RNG for the prover with the given zero-knowledge variant
+pub fn prover_rng(seed: u64, variant: u64) -> ChaCha20Rng {
+    stream_rng(seed, Stream::Prover as u64 + variant)
+}
+
+fn stream_rng(seed: u64, stream: u64) -> ChaCha20Rng {
+    let mut rng = ChaCha20Rng::seed_from_u64(seed);
+    rng.set_stream(stream);
+    rng
+}
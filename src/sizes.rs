@@ -0,0 +1,69 @@
+use ark_ff::PrimeField;
+use ark_marlin::{IndexProverKey, IndexVerifierKey, Proof, UniversalSRS};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly_commit::PolynomialCommitment;
+use ark_serialize::CanonicalSerialize;
+use serde::{Deserialize, Serialize};
+
+/// Serialized size in bytes, with and without point compression
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Size {
+    pub compressed: usize,
+    pub uncompressed: usize,
+}
+
+impl Size {
+    pub fn of<T: CanonicalSerialize>(value: &T) -> Self {
+        Size {
+            compressed: value.serialized_size(),
+            uncompressed: value.uncompressed_size(),
+        }
+    }
+}
+
+/// Sizes of every artifact produced by a run
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ArtifactSizes {
+    pub srs: Size,
+    pub proving_key: Size,
+    pub verifying_key: Size,
+    pub proof: Size,
+    // Parts of the proof
+    pub proof_commitments: Size,
+    pub proof_evaluations: Size,
+    pub proof_prover_messages: Size,
+    pub proof_opening: Size,
+}
+
+impl ArtifactSizes {
+    pub fn measure<F: PrimeField, PC: PolynomialCommitment<F, DensePolynomial<F>>>(
+        srs: &UniversalSRS<F, PC>,
+        pk: &IndexProverKey<F, PC>,
+        vk: &IndexVerifierKey<F, PC>,
+        proof: &Proof<F, PC>,
+    ) -> Self {
+        ArtifactSizes {
+            srs: Size::of(srs),
+            proving_key: Size::of(pk),
+            verifying_key: Size::of(vk),
+            proof: Size::of(proof),
+            proof_commitments: Size::of(&proof.commitments),
+            proof_evaluations: Size::of(&proof.evaluations),
+            proof_prover_messages: Size::of(&proof.prover_messages),
+            proof_opening: Size::of(&proof.pc_proof),
+        }
+    }
+
+    pub fn summaries(&self) -> Vec<(&'static str, Size)> {
+        vec![
+            ("SRS", self.srs),
+            ("Proving key", self.proving_key),
+            ("Verifying key", self.verifying_key),
+            ("Proof", self.proof),
+            ("    commitments", self.proof_commitments),
+            ("    evaluations", self.proof_evaluations),
+            ("    prover messages", self.proof_prover_messages),
+            ("    opening proof", self.proof_opening),
+        ]
+    }
+}
@@ -0,0 +1,193 @@
+use std::panic::{self, AssertUnwindSafe};
+
+use ark_ff::PrimeField;
+use ark_marlin::ahp::prover::ProverMsg;
+use ark_marlin::{IndexVerifierKey, Proof, UniversalSRS};
+use ark_poly_commit::PCUniversalParams;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::bench::synthesize;
+use crate::instrumented::{IndexerTimings, InstrumentedMarlin, ProverTimings, VerifierTimings};
+use crate::pc::BenchPC;
+use crate::public_inputs::WithPublicInputs;
+use crate::circuit_traits::BenchCircuit;
+use crate::seed::{self, Stream};
+use crate::transcript::Transcript;
+use crate::{srs, RunConfig};
+
+/// How the verifier handled a tampered input
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Accepted,
+    Rejected,
+    // The verifier returned an error
+    Error,
+    // The verifier panicked, e.g. on a failed assertion of the commitment scheme
+    Panic,
+}
+
+/// Tampered input given to the verifier and how it was handled
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SoundnessCase {
+    pub case: String,
+    pub outcome: Outcome,
+}
+
+impl SoundnessCase {
+    pub fn rejected(&self) -> bool {
+        self.outcome != Outcome::Accepted
+    }
+}
+
+/// Copy of the proof modified by `tamper`, going through its serialization as
+/// Marlin proofs cannot be cloned
+pub fn tampered<F: PrimeField, P: BenchPC<F>>(
+    proof: &Proof<F, P>,
+    tamper: impl FnOnce(&mut Proof<F, P>),
+) -> Proof<F, P> {
+    let mut copy = Proof::<F, P>::deserialize(&serialized(proof)[..]).unwrap();
+    tamper(&mut copy);
+    copy
+}
+
+fn verify<F: PrimeField, P: BenchPC<F>, FS: Transcript>(
+    config: &RunConfig,
+    vk: &IndexVerifierKey<F, P>,
+    inputs: &[F],
+    proof: &Proof<F, P>,
+) -> Outcome {
+    let rng = &mut seed::rng(config.seed, Stream::Verifier);
+
+    // Silence the panic message while the verifier runs
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        InstrumentedMarlin::<F, P, FS>::verify(vk, inputs, proof, rng, &mut VerifierTimings::default())
+    }));
+    panic::set_hook(hook);
+
+    match result {
+        Ok(Ok(true)) => Outcome::Accepted,
+        Ok(Ok(false)) => Outcome::Rejected,
+        Ok(Err(_)) => Outcome::Error,
+        Err(_) => Outcome::Panic,
+    }
+}
+
+fn serialized<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize(&mut bytes).unwrap();
+    bytes
+}
+
+/// Gives the verifier tampered versions of an honest proof, its public inputs
+/// and its verifying key, all of which should be rejected
+pub fn check<F: PrimeField, C: BenchCircuit<F>, P: BenchPC<F>, FS: Transcript>(
+    config: &RunConfig,
+    params: &C::Params,
+    srs: &UniversalSRS<F, P>,
+    vk: &IndexVerifierKey<F, P>,
+    inputs: &[F],
+    proof: &Proof<F, P>,
+) -> Vec<SoundnessCase> {
+    let mut cases = Vec::new();
+    let mut case = |name: String, outcome: Outcome| cases.push(SoundnessCase { case: name, outcome });
+
+    for i in 0..inputs.len() {
+        let mut wrong = inputs.to_vec();
+        wrong[i] += F::one();
+        case(format!("public input {} + 1", i), verify::<F, P, FS>(config, vk, &wrong, proof));
+    }
+
+    // Every commitment replaced by the next different one of the same shape,
+    // i.e. with a shifted commitment only if the original has one
+    let comms: Vec<(usize, usize, Vec<u8>)> = proof
+        .commitments
+        .iter()
+        .enumerate()
+        .flat_map(|(round, comms)| comms.iter().enumerate().map(move |(i, comm)| (round, i, serialized(comm))))
+        .collect();
+    for (k, &(round, i, ref bytes)) in comms.iter().enumerate() {
+        let replacement = comms
+            .iter()
+            .cycle()
+            .skip(k + 1)
+            .take(comms.len())
+            .find(|(_, _, other)| other.len() == bytes.len() && other != bytes);
+        let Some((other_round, other_i, _)) = replacement else {
+            continue;
+        };
+        let other = proof.commitments[*other_round][*other_i].clone();
+        let wrong = tampered::<F, P>(proof, |p| p.commitments[round][i] = other);
+        case(
+            format!("commitment {}.{} replaced", round, i),
+            verify::<F, P, FS>(config, vk, inputs, &wrong),
+        );
+    }
+
+    for i in 0..proof.evaluations.len() {
+        let wrong = tampered::<F, P>(proof, |p| p.evaluations[i] += F::one());
+        case(format!("evaluation {} + 1", i), verify::<F, P, FS>(config, vk, inputs, &wrong));
+    }
+
+    for (round, msg) in proof.prover_messages.iter().enumerate() {
+        if let ProverMsg::FieldElements(elems) = msg {
+            for i in 0..elems.len() {
+                let wrong = tampered::<F, P>(proof, |p| {
+                    if let ProverMsg::FieldElements(elems) = &mut p.prover_messages[round] {
+                        elems[i] += F::one();
+                    }
+                });
+                case(
+                    format!("prover message {}.{} + 1", round, i),
+                    verify::<F, P, FS>(config, vk, inputs, &wrong),
+                );
+            }
+        }
+    }
+
+    let num_opening_evals = proof.pc_proof.evals.as_ref().map_or(0, Vec::len);
+    for i in 0..num_opening_evals {
+        let wrong = tampered::<F, P>(proof, |p| p.pc_proof.evals.as_mut().unwrap()[i] += F::one());
+        case(format!("opening evaluation {} + 1", i), verify::<F, P, FS>(config, vk, inputs, &wrong));
+    }
+
+    // Another index: the same circuit with one more round
+    let circuit_rng = &mut seed::rng(config.seed, Stream::Circuit);
+    let other = WithPublicInputs::new(C::new_random(params, circuit_rng, config.rounds + 1), config.public_inputs, circuit_rng);
+    let other_inputs = other.public_inputs();
+    let max_degree = srs::required_degree(&synthesize(&other).0);
+    let bigger_srs;
+    let other_srs = if max_degree <= srs.max_degree() {
+        srs
+    } else {
+        bigger_srs = P::setup(max_degree, None, &mut seed::rng(config.seed, Stream::Setup)).unwrap();
+        &bigger_srs
+    };
+    let (other_pk, other_vk) =
+        InstrumentedMarlin::<F, P, FS>::index(other_srs, other.clone(), &mut IndexerTimings::default()).unwrap();
+    let other_proof = InstrumentedMarlin::<F, P, FS>::prove(
+        &other_pk,
+        other,
+        &mut seed::prover_rng(config.seed, 0),
+        &mut ProverTimings::default(),
+    )
+    .unwrap();
+
+    case(
+        "proof of another circuit".to_string(),
+        verify::<F, P, FS>(config, vk, inputs, &other_proof),
+    );
+    case(
+        "proof of another circuit with its inputs".to_string(),
+        verify::<F, P, FS>(config, vk, &other_inputs, &other_proof),
+    );
+    case(
+        "verifying key of another index".to_string(),
+        verify::<F, P, FS>(config, &other_vk, inputs, proof),
+    );
+
+    cases
+}
@@ -0,0 +1,131 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ark_ec::{AffineCurve, PairingEngine};
+use ark_ff::PrimeField;
+use ark_marlin::ahp::AHPForR1CS;
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_poly_commit::{PCUniversalParams, PolynomialCommitment};
+use ark_relations::r1cs::ConstraintMatrices;
+
+use crate::artifact;
+use crate::bench::synthesize;
+use crate::circuit_traits::BenchCircuit;
+use crate::pc::PcScheme;
+use crate::public_inputs::WithPublicInputs;
+use crate::registry::{self, CircuitVisitor, CurveVisitor, GroupVisitor};
+use crate::RunConfig;
+use crate::printers::Colorize;
+use crate::print_panic;
+use crate::seed::{self, Stream};
+
+/// Maximum degree the SRS needs to index the circuit with these matrices, once
+/// Marlin pads its public inputs to a power of two and makes them square
+pub fn required_degree<F: PrimeField>(matrices: &ConstraintMatrices<F>) -> usize {
+    let padded_instance = GeneralEvaluationDomain::<F>::compute_size_of_domain(matrices.num_instance_variables)
+        .unwrap_or_else(|| print_panic!("There is no domain for {} public inputs", matrices.num_instance_variables));
+    let num_non_zero = matrices.a_num_non_zero.max(matrices.b_num_non_zero).max(matrices.c_num_non_zero);
+    AHPForR1CS::<F>::max_degree(
+        matrices.num_constraints,
+        padded_instance + matrices.num_witness_variables,
+        num_non_zero,
+    )
+    .unwrap_or_else(|e| print_panic!("The circuit is too big for this field: {:?}", e))
+}
+
+// Degree of the circuit of the config over each visited curve or group
+struct DegreeOnCurve<'a>(&'a RunConfig);
+
+// Degree of each visited circuit over the field `F`
+struct DegreeOfCircuit<'a>(&'a RunConfig);
+
+impl CurveVisitor for DegreeOnCurve<'_> {
+    type Output = Option<usize>;
+
+    fn visit<E: PairingEngine>(self) -> Self::Output {
+        registry::visit_circuit::<E::Fr, _>(&self.0.circuit, DegreeOfCircuit(self.0))
+    }
+}
+
+impl GroupVisitor for DegreeOnCurve<'_> {
+    type Output = Option<usize>;
+
+    fn visit<G: AffineCurve>(self) -> Self::Output {
+        registry::visit_circuit::<G::ScalarField, _>(&self.0.circuit, DegreeOfCircuit(self.0))
+    }
+}
+
+impl<F: PrimeField> CircuitVisitor<F> for DegreeOfCircuit<'_> {
+    type Output = usize;
+
+    fn visit<C: BenchCircuit<F>>(self, params: C::Params) -> Self::Output {
+        let rng = &mut seed::rng(self.0.seed, Stream::Circuit);
+        let c = WithPublicInputs::new(C::new_random(&params, rng, self.0.rounds), self.0.public_inputs, rng);
+        required_degree(&synthesize(&c).0)
+    }
+}
+
+/// Maximum degree the SRS needs for the circuit of a Marlin config, the same
+/// instance the benchmark builds, or None if its circuit or curve does not exist
+pub fn required_degree_of(config: &RunConfig) -> Option<usize> {
+    match config.pc {
+        PcScheme::Ipa => registry::visit_group(&config.curve, DegreeOnCurve(config)),
+        _ => registry::visit_curve(&config.curve, DegreeOnCurve(config)),
+    }
+    .flatten()
+}
+
+// Files are named <curve>-seed<seed>-<max degree>.srs, so that an SRS is only
+// reused by runs with the seed that generated it
+fn srs_path(dir: &Path, curve: &str, seed: u64, max_degree: usize) -> PathBuf {
+    dir.join(format!("{}-seed{}-{}.srs", curve, seed, max_degree))
+}
+
+// Smallest stored SRS of the curve and seed that supports `max_degree`
+fn find_srs(dir: &Path, curve: &str, seed: u64, max_degree: usize) -> Option<PathBuf> {
+    let prefix = format!("{}-seed{}-", curve, seed);
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            let degree: usize = name.strip_prefix(&prefix)?.strip_suffix(".srs")?.parse().ok()?;
+            Some(degree)
+        })
+        .filter(|degree| *degree >= max_degree)
+        .min()
+        .map(|degree| srs_path(dir, curve, seed, degree))
+}
+
+/// Loads the smallest stored SRS generated with `seed` that supports
+/// `max_degree`, or generates a new one of at least `min_degree` from the setup
+/// stream of `seed` and stores it. Without a directory the SRS is always
+/// generated. Returns whether the SRS was loaded.
+pub fn load_or_setup<F: PrimeField, PC: PolynomialCommitment<F, DensePolynomial<F>>>(
+    dir: Option<&Path>,
+    curve: &str,
+    seed: u64,
+    max_degree: usize,
+    min_degree: usize,
+) -> (PC::UniversalParams, bool) {
+    if let Some(path) = dir.and_then(|dir| find_srs(dir, curve, seed, max_degree)) {
+        match artifact::read_unchecked::<PC::UniversalParams>(&path, artifact::SRS, curve) {
+            Ok(srs) if srs.max_degree() >= max_degree => return (srs, true),
+            // On stderr, which keeps the JSON output parseable
+            Ok(_) => eprintln!("{} Ignoring stored SRS, {} is too small", "Info:".yellow().bold(), path.display()),
+            Err(e) => eprintln!("{} Ignoring stored SRS, {}", "Info:".yellow().bold(), e),
+        }
+    }
+
+    let srs = PC::setup(max_degree.max(min_degree), None, &mut seed::rng(seed, Stream::Setup))
+        .unwrap_or_else(|e| print_panic!("Setup failed: {:?}", e));
+
+    if let Some(dir) = dir {
+        let path = srs_path(dir, curve, seed, srs.max_degree());
+        fs::create_dir_all(dir)
+            .and_then(|_| artifact::write(&path, artifact::SRS, curve, &srs))
+            .unwrap_or_else(|e| print_panic!("Cannot write {}: {}", path.display(), e));
+    }
+
+    (srs, false)
+}
@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+
+// Two sided 95% quantiles of the Student t distribution for 1..=30 degrees of freedom
+const T_95: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228,
+    2.201, 2.179, 2.160, 2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086,
+    2.080, 2.074, 2.069, 2.064, 2.060, 2.056, 2.052, 2.048, 2.045, 2.042,
+];
+
+/// Summary of the repeated measurements of one phase, in nanoseconds
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PhaseStats {
+    pub samples_ns: Vec<u64>,
+    pub min_ns: u64,
+    pub max_ns: u64,
+    pub median_ns: f64,
+    pub mean_ns: f64,
+    pub stddev_ns: f64,
+    // 95% confidence interval of the mean
+    pub ci_low_ns: f64,
+    pub ci_high_ns: f64,
+    // Indices of the samples outside the Tukey fences (1.5 IQR)
+    pub outliers: Vec<usize>,
+}
+
+// Linear interpolation between the closest ranks of a sorted slice
+fn quantile(sorted: &[u64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let low = pos.floor() as usize;
+    let high = pos.ceil() as usize;
+    sorted[low] as f64 + (sorted[high] as f64 - sorted[low] as f64) * (pos - low as f64)
+}
+
+impl PhaseStats {
+    pub fn from_samples(samples_ns: Vec<u64>) -> Self {
+        assert!(!samples_ns.is_empty(), "at least one sample is needed");
+
+        let mut sorted = samples_ns.clone();
+        sorted.sort_unstable();
+        let n = sorted.len();
+
+        let mean = sorted.iter().map(|s| *s as f64).sum::<f64>() / n as f64;
+        let stddev = match n {
+            1 => 0.0,
+            _ => (sorted.iter().map(|s| (*s as f64 - mean).powi(2)).sum::<f64>() / (n - 1) as f64).sqrt(),
+        };
+
+        let t = match n {
+            1 => 0.0,
+            _ => T_95.get(n - 2).copied().unwrap_or(1.96),
+        };
+        let margin = t * stddev / (n as f64).sqrt();
+
+        let q1 = quantile(&sorted, 0.25);
+        let q3 = quantile(&sorted, 0.75);
+        let iqr = q3 - q1;
+        let outliers = samples_ns
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| (**s as f64) < q1 - 1.5 * iqr || (**s as f64) > q3 + 1.5 * iqr)
+            .map(|(i, _)| i)
+            .collect();
+
+        PhaseStats {
+            min_ns: sorted[0],
+            max_ns: sorted[n - 1],
+            median_ns: quantile(&sorted, 0.5),
+            mean_ns: mean,
+            stddev_ns: stddev,
+            ci_low_ns: mean - margin,
+            ci_high_ns: mean + margin,
+            outliers,
+            samples_ns,
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        if self.samples_ns.len() == 1 {
+            return format_ns(self.mean_ns);
+        }
+
+        format!(
+            "median {}, mean {} ± {}, min {}, 95% CI [{}, {}], {} outliers over {} iterations",
+            format_ns(self.median_ns),
+            format_ns(self.mean_ns),
+            format_ns(self.stddev_ns),
+            format_ns(self.min_ns as f64),
+            format_ns(self.ci_low_ns),
+            format_ns(self.ci_high_ns),
+            self.outliers.len(),
+            self.samples_ns.len(),
+        )
+    }
+}
+
+/// Formats a duration in nanoseconds with the most readable unit
+pub fn format_ns(ns: f64) -> String {
+    if ns.abs() >= 1e9 {
+        format!("{:.3}s", ns / 1e9)
+    } else if ns.abs() >= 1e6 {
+        format!("{:.3}ms", ns / 1e6)
+    } else if ns.abs() >= 1e3 {
+        format!("{:.3}µs", ns / 1e3)
+    } else {
+        format!("{:.0}ns", ns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PhaseStats;
+
+    fn assert_close(value: f64, expected: f64) {
+        assert!((value - expected).abs() < 1e-3, "{} is not {}", value, expected);
+    }
+
+    #[test]
+    fn median_and_confidence_interval() {
+        let stats = PhaseStats::from_samples(vec![4, 2, 5, 1, 3]);
+        assert_eq!((stats.min_ns, stats.max_ns), (1, 5));
+        assert_close(stats.median_ns, 3.0);
+        assert_close(stats.mean_ns, 3.0);
+        assert_close(stats.stddev_ns, 2.5f64.sqrt());
+        // t = 2.776 for 4 degrees of freedom
+        let margin = 2.776 * 2.5f64.sqrt() / 5f64.sqrt();
+        assert_close(stats.ci_low_ns, 3.0 - margin);
+        assert_close(stats.ci_high_ns, 3.0 + margin);
+        assert!(stats.outliers.is_empty());
+    }
+
+    #[test]
+    fn median_of_an_even_number_of_samples() {
+        assert_close(PhaseStats::from_samples(vec![1, 2, 3, 10]).median_ns, 2.5);
+    }
+
+    #[test]
+    fn outliers_are_indices_of_the_samples() {
+        // Quartiles 11 and 13, so the fences are 8 and 16
+        let stats = PhaseStats::from_samples(vec![100, 12, 10, 13, 11]);
+        assert_close(stats.median_ns, 12.0);
+        assert_eq!(stats.outliers, vec![0]);
+    }
+
+    #[test]
+    fn single_sample() {
+        let stats = PhaseStats::from_samples(vec![7]);
+        assert_close(stats.median_ns, 7.0);
+        assert_close(stats.stddev_ns, 0.0);
+        assert_close(stats.ci_low_ns, 7.0);
+        assert_close(stats.ci_high_ns, 7.0);
+    }
+}
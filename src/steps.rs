@@ -0,0 +1,255 @@
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use ark_ec::PairingEngine;
+use ark_marlin::{IndexProverKey, IndexVerifierKey, Proof, UniversalSRS};
+use ark_poly_commit::{PCUniversalParams, PolynomialCommitment};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use clap::{Args as ClapArgs, Subcommand};
+
+use crate::artifact;
+use crate::bench::{synthesize, UpstreamMarlin, PC};
+use crate::circuit_traits::BenchCircuit;
+use crate::public_inputs::WithPublicInputs;
+use crate::registry::{self, CircuitVisitor, CurveVisitor};
+use crate::seed::{self, Stream};
+use crate::srs;
+use crate::stats::format_ns;
+use crate::{print_info, print_panic};
+
+/// Circuit instance shared by the setup, index and prove steps and the export
+#[derive(ClapArgs, Debug)]
+pub struct CircuitArgs {
+    /// Circuit, see --list-circuits
+    #[arg(short, long, default_value = "basic")]
+    pub system: String,
+
+    /// Number of rounds
+    #[arg(short, long, default_value_t = 1)]
+    pub rounds: usize,
+
+    /// Curve, see --list-curves
+    #[arg(short, long, default_value = "bls12_381")]
+    pub curve: String,
+
+    /// Extra public inputs added to the circuit, each bound to a witness variable
+    #[arg(long, default_value_t = 0)]
+    pub public_inputs: usize,
+
+    /// Seed of the RNG used for the circuit, the SRS and the proof
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct SetupArgs {
+    #[command(flatten)]
+    circuit: CircuitArgs,
+
+    /// Minimum degree of the SRS, so that bigger circuits can use it
+    #[arg(long, default_value_t = 0)]
+    max_degree: usize,
+
+    /// Where the SRS is written
+    #[arg(long, default_value = "srs.bin")]
+    srs: PathBuf,
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct IndexArgs {
+    #[command(flatten)]
+    circuit: CircuitArgs,
+
+    /// SRS written by the setup step
+    #[arg(long, default_value = "srs.bin")]
+    srs: PathBuf,
+
+    /// Where the proving key is written
+    #[arg(long, default_value = "pk.bin")]
+    pk: PathBuf,
+
+    /// Where the verifying key is written
+    #[arg(long, default_value = "vk.bin")]
+    vk: PathBuf,
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct ProveArgs {
+    #[command(flatten)]
+    circuit: CircuitArgs,
+
+    /// Proving key written by the index step
+    #[arg(long, default_value = "pk.bin")]
+    pk: PathBuf,
+
+    /// Where the proof is written
+    #[arg(long, default_value = "proof.bin")]
+    proof: PathBuf,
+
+    /// Where the public inputs are written
+    #[arg(long, default_value = "inputs.bin")]
+    inputs: PathBuf,
+}
+
+#[derive(ClapArgs, Debug)]
+pub struct VerifyArgs {
+    /// Curve, see --list-curves
+    #[arg(short, long, default_value = "bls12_381")]
+    curve: String,
+
+    /// Seed of the RNG used by the verifier
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Verifying key written by the index step
+    #[arg(long, default_value = "vk.bin")]
+    vk: PathBuf,
+
+    /// Proof written by the prove step
+    #[arg(long, default_value = "proof.bin")]
+    proof: PathBuf,
+
+    /// Public inputs written by the prove step
+    #[arg(long, default_value = "inputs.bin")]
+    inputs: PathBuf,
+}
+
+/// Steps of the pipeline run in isolation, passing the artifacts through files.
+/// They always run Marlin with MarlinKZG10 and the Blake2s transcript.
+#[derive(Subcommand, Debug)]
+pub enum Step {
+    /// Generate the universal SRS big enough for a circuit
+    Setup(SetupArgs),
+    /// Generate the proving and verifying keys of a circuit
+    Index(IndexArgs),
+    /// Generate a proof and its public inputs
+    Prove(ProveArgs),
+    /// Check a proof against a verifying key
+    Verify(VerifyArgs),
+}
+
+impl Step {
+    fn curve(&self) -> &str {
+        match self {
+            Step::Setup(args) => &args.circuit.curve,
+            Step::Index(args) => &args.circuit.curve,
+            Step::Prove(args) => &args.circuit.curve,
+            Step::Verify(args) => &args.curve,
+        }
+    }
+}
+
+fn load<T: CanonicalDeserialize>(path: &Path, magic: &[u8; 8], curve: &str) -> T {
+    artifact::read(path, magic, curve).unwrap_or_else(|e| print_panic!("{}", e))
+}
+
+fn store<T: CanonicalSerialize>(path: &Path, magic: &[u8; 8], curve: &str, value: &T) {
+    artifact::write(path, magic, curve, value)
+        .unwrap_or_else(|e| print_panic!("Cannot write {}: {}", path.display(), e));
+    print_info!("Wrote {}", path.display());
+}
+
+/// Same circuit instance the benchmark uses
+pub fn circuit<E: PairingEngine, C: BenchCircuit<E::Fr>>(args: &CircuitArgs, params: &C::Params) -> WithPublicInputs<E::Fr, C> {
+    if args.rounds == 0 {
+        print_panic!("0 is not a valid number of rounds")
+    }
+    let rng = &mut seed::rng(args.seed, Stream::Circuit);
+    WithPublicInputs::new(C::new_random(params, rng, args.rounds), args.public_inputs, rng)
+}
+
+fn setup<E: PairingEngine, C: BenchCircuit<E::Fr>>(args: &SetupArgs, params: &C::Params) {
+    let max_degree = srs::required_degree(&synthesize(&circuit::<E, C>(&args.circuit, params)).0).max(args.max_degree);
+
+    let start = Instant::now();
+    let srs: UniversalSRS<E::Fr, PC<E>> = PC::<E>::setup(max_degree, None, &mut seed::rng(args.circuit.seed, Stream::Setup))
+        .unwrap_or_else(|e| print_panic!("Setup failed: {:?}", e));
+    print_info!("Setup time: {}", format_ns(start.elapsed().as_nanos() as f64));
+    print_info!("SRS max degree: {}", srs.max_degree());
+
+    store(&args.srs, artifact::SRS, &args.circuit.curve, &srs);
+}
+
+fn index<E: PairingEngine, C: BenchCircuit<E::Fr>>(args: &IndexArgs, params: &C::Params) {
+    let c = circuit::<E, C>(&args.circuit, params);
+    let srs: UniversalSRS<E::Fr, PC<E>> = load(&args.srs, artifact::SRS, &args.circuit.curve);
+
+    let start = Instant::now();
+    let (pk, vk) = UpstreamMarlin::<E>::index(&srs, c).unwrap_or_else(|e| print_panic!("Index failed: {:?}", e));
+    print_info!("Indexer time: {}", format_ns(start.elapsed().as_nanos() as f64));
+
+    store(&args.pk, artifact::PROVING_KEY, &args.circuit.curve, &pk);
+    store(&args.vk, artifact::VERIFYING_KEY, &args.circuit.curve, &vk);
+}
+
+fn prove<E: PairingEngine, C: BenchCircuit<E::Fr>>(args: &ProveArgs, params: &C::Params) {
+    let c = circuit::<E, C>(&args.circuit, params);
+    let pk: IndexProverKey<E::Fr, PC<E>> = load(&args.pk, artifact::PROVING_KEY, &args.circuit.curve);
+    let inputs = c.public_inputs();
+
+    let start = Instant::now();
+    let proof = UpstreamMarlin::<E>::prove(&pk, c, &mut seed::prover_rng(args.circuit.seed, 0)).unwrap_or_else(|e| print_panic!("Prove failed: {:?}", e));
+    print_info!("Prover time: {}", format_ns(start.elapsed().as_nanos() as f64));
+
+    store(&args.proof, artifact::PROOF, &args.circuit.curve, &proof);
+    store(&args.inputs, artifact::PUBLIC_INPUTS, &args.circuit.curve, &inputs);
+}
+
+fn verify<E: PairingEngine>(args: &VerifyArgs) {
+    let vk: IndexVerifierKey<E::Fr, PC<E>> = load(&args.vk, artifact::VERIFYING_KEY, &args.curve);
+    let proof: Proof<E::Fr, PC<E>> = load(&args.proof, artifact::PROOF, &args.curve);
+    let inputs: Vec<E::Fr> = load(&args.inputs, artifact::PUBLIC_INPUTS, &args.curve);
+
+    let start = Instant::now();
+    let res = UpstreamMarlin::<E>::verify(&vk, &inputs, &proof, &mut seed::rng(args.seed, Stream::Verifier))
+        .unwrap_or_else(|e| print_panic!("Verify failed: {:?}", e));
+    print_info!("Verifier time: {}", format_ns(start.elapsed().as_nanos() as f64));
+    print_info!("Verification: {}", res);
+
+    if !res {
+        std::process::exit(1);
+    }
+}
+
+// Runs the step over each visited curve
+struct StepOnCurve<'a>(&'a Step);
+
+// Runs the step with each visited circuit over the pairing engine `E`
+struct StepOnCircuit<'a, E>(&'a Step, std::marker::PhantomData<E>);
+
+impl CurveVisitor for StepOnCurve<'_> {
+    type Output = Option<()>;
+
+    fn visit<E: PairingEngine>(self) -> Self::Output {
+        let circuit = match self.0 {
+            Step::Setup(args) => &args.circuit.system,
+            Step::Index(args) => &args.circuit.system,
+            Step::Prove(args) => &args.circuit.system,
+            Step::Verify(args) => {
+                verify::<E>(args);
+                return Some(());
+            }
+        };
+        registry::visit_circuit(circuit, StepOnCircuit::<E>(self.0, Default::default()))
+    }
+}
+
+impl<E: PairingEngine> CircuitVisitor<E::Fr> for StepOnCircuit<'_, E> {
+    type Output = ();
+
+    fn visit<C: BenchCircuit<E::Fr>>(self, params: C::Params) -> Self::Output {
+        match self.0 {
+            Step::Setup(args) => setup::<E, C>(args, &params),
+            Step::Index(args) => index::<E, C>(args, &params),
+            Step::Prove(args) => prove::<E, C>(args, &params),
+            Step::Verify(_) => unreachable!(),
+        }
+    }
+}
+
+/// Runs a single step of the pipeline
+pub fn run(step: &Step) {
+    registry::visit_curve(step.curve(), StepOnCurve(step))
+        .flatten()
+        .unwrap_or_else(|| print_panic!("Invalid circuit or curve {}", step.curve()))
+}
@@ -15,25 +15,27 @@ pub struct SumCircuit<F: PrimeField> {
 
 // Constructor for SumCircuit
 impl<F: PrimeField> BenchCircuit<F> for SumCircuit<F> {
-    fn new_random<R: RngCore>(rng: &mut R, rounds: usize) -> Self {
+    type Params = ();
+
+    fn new_random<R: RngCore>(_params: &(), rng: &mut R, rounds: usize) -> Self {
         SumCircuit { 
             x: <F>::rand(rng),  
             t: rounds + 3 
         }
     }
 
-    fn get_result(&self) -> F {
+    fn public_inputs(&self) -> Vec<F> {
         let mut v: Vec<F> = Vec::new();
 
         v.push(self.x);
         for _ in 0..(self.t - 1) {
-            let mut r: F = self.x - self.x; // This is 0
+            let mut r = F::zero();
             for value in &v {
-                r = r + value;
+                r += value;
             }
             v.push(r);
         }
-        v.last().unwrap().clone()
+        vec![*v.last().unwrap()]
     }
 } 
 
@@ -51,7 +53,7 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for SumCircuit<F> {
         let x = cs.new_witness_variable(|| Ok(x_val))?;
 
         // Allocate one
-        let one_val = <F>::from(1 as u32);
+        let one_val = <F>::from(1u32);
         let one = cs.new_witness_variable(|| Ok(one_val))?;
 
         let mut v_val: Vec<F> = Vec::new();
@@ -60,9 +62,9 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for SumCircuit<F> {
         v_val.push(x_val);
         v.push(x);
         for _ in 0..(self.t - 2) {
-            let mut r_val = x_val - x_val; // This is 0
+            let mut r_val = F::zero();
             for value in &v_val {
-                r_val = r_val + value;
+                r_val += value;
             }
             v_val.push(r_val);
 
@@ -76,9 +78,9 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for SumCircuit<F> {
             cs.enforce_constraint(lc.clone(), lc!() + one, lc!() + r)?;
         }
 
-        let mut out_val = x_val - x_val; // This is 0
+        let mut out_val = F::zero();
         for value in &v_val {
-            out_val = out_val + value;
+            out_val += value;
         }
 
         let out = cs.new_input_variable(|| Ok(out_val))?;
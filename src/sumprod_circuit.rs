@@ -15,25 +15,27 @@ pub struct SumProdCircuit<F: PrimeField> {
 
 // Constructor for SumProdCircuit
 impl<F: PrimeField> BenchCircuit<F> for SumProdCircuit<F> {
-    fn new_random<R: RngCore>(rng: &mut R, rounds: usize) -> Self {
+    type Params = ();
+
+    fn new_random<R: RngCore>(_params: &(), rng: &mut R, rounds: usize) -> Self {
         SumProdCircuit { 
             x: <F>::rand(rng),  
             t: rounds + 3 
         }
     }
 
-    fn get_result(&self) -> F {
+    fn public_inputs(&self) -> Vec<F> {
         let mut v: Vec<F> = Vec::new();
 
         v.push(self.x);
         for _ in 0..(self.t - 1) {
-            let mut r: F = self.x - self.x; // This is 0
+            let mut r = F::zero();
             for value in &v {
-                r = r + value;
+                r += value;
             }
             v.push(r * r);
         }
-        v.last().unwrap().clone()
+        vec![*v.last().unwrap()]
     }
 } 
 
@@ -56,9 +58,9 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for SumProdCircuit<F> {
         v_val.push(x_val);
         v.push(x);
         for _ in 0..(self.t - 2) {
-            let mut r_val = x_val - x_val; // This is 0
+            let mut r_val = F::zero();
             for value in &v_val {
-                r_val = r_val + value;
+                r_val += value;
             }
             r_val = r_val * r_val;
             v_val.push(r_val);
@@ -73,9 +75,9 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for SumProdCircuit<F> {
             cs.enforce_constraint(lc.clone(), lc, lc!() + r)?;
         }
 
-        let mut out_val = x_val - x_val; // This is 0
+        let mut out_val = F::zero();
         for value in &v_val {
-            out_val = out_val + value;
+            out_val += value;
         }
         out_val = out_val * out_val;
 
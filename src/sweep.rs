@@ -0,0 +1,614 @@
+use std::collections::HashMap;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use clap::Args as ClapArgs;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::file_circuit;
+use crate::pc::PcScheme;
+use crate::registry;
+use crate::srs;
+use crate::report::{system_name, Backend, RunResult};
+use crate::transcript::FsScheme;
+use crate::stats::format_ns;
+use crate::{print_info, print_panic, RunConfig};
+
+#[derive(ClapArgs, Debug)]
+pub struct SweepArgs {
+    /// Rounds to run, e.g. "32,64", "2^5..2^15" or "1..8"
+    #[arg(short, long, default_value = "1")]
+    rounds: String,
+
+    /// Circuits to run
+    #[arg(short = 's', long, value_delimiter = ',', default_value = "basic")]
+    circuits: Vec<String>,
+
+    /// Curves to run
+    #[arg(short, long, value_delimiter = ',', default_value = "bls12_381")]
+    curves: Vec<String>,
+
+    /// Proving systems to run on every point
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "marlin")]
+    backends: Vec<Backend>,
+
+    /// Commitment schemes Marlin runs with on every point
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "marlin_kzg10")]
+    pcs: Vec<PcScheme>,
+
+    /// Fiat-Shamir transcripts Marlin runs with on every point
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "blake2s")]
+    fs: Vec<FsScheme>,
+
+    /// Extra public inputs added to the circuit of every point
+    #[arg(long, value_delimiter = ',', default_value = "0")]
+    public_inputs: Vec<usize>,
+
+    /// TOML suite with named experiments, overrides the lists above
+    #[arg(long)]
+    suite: Option<PathBuf>,
+
+    /// Untimed repetitions of every phase before measuring
+    #[arg(long, default_value_t = 0)]
+    warmup: usize,
+
+    /// Timed repetitions of every phase
+    #[arg(long, default_value_t = 1)]
+    iterations: usize,
+
+    /// Threads used by every point, 0 for one per core
+    #[arg(long, default_value_t = 0)]
+    threads: usize,
+
+    /// Measure the memory of every phase, which slows down the allocations
+    #[arg(long)]
+    memory: bool,
+
+    /// Seed of the RNG used for every point
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Directory where each point and the summaries are stored
+    #[arg(long, default_value = "results")]
+    out_dir: PathBuf,
+
+    /// Directory where the SRS is shared between points, <out-dir>/srs by default
+    #[arg(long)]
+    srs_dir: Option<PathBuf>,
+
+    /// Minimum degree of a generated SRS, which is always big enough for every point
+    #[arg(long, default_value_t = 0)]
+    srs_max_degree: usize,
+}
+
+/// A named cross product of circuits, curves and rounds
+#[derive(Debug, Deserialize)]
+pub struct Experiment {
+    pub name: String,
+    pub circuits: Vec<String>,
+    pub curves: Vec<String>,
+    pub rounds: String,
+    #[serde(default = "default_backends")]
+    pub backends: Vec<Backend>,
+    #[serde(default = "default_pcs")]
+    pub pcs: Vec<PcScheme>,
+    #[serde(default = "default_fs")]
+    pub fs: Vec<FsScheme>,
+    #[serde(default = "default_public_inputs")]
+    pub public_inputs: Vec<usize>,
+}
+
+fn default_backends() -> Vec<Backend> {
+    vec![Backend::Marlin]
+}
+
+fn default_pcs() -> Vec<PcScheme> {
+    vec![PcScheme::MarlinKzg10]
+}
+
+fn default_fs() -> Vec<FsScheme> {
+    vec![FsScheme::Blake2s]
+}
+
+fn default_public_inputs() -> Vec<usize> {
+    vec![0]
+}
+
+impl Experiment {
+    // Every backend, with Marlin once per commitment scheme and transcript
+    fn systems(&self) -> Vec<(Backend, PcScheme, FsScheme)> {
+        self.backends
+            .iter()
+            .flat_map(|backend| match backend {
+                Backend::Marlin => self
+                    .pcs
+                    .iter()
+                    .flat_map(|pc| self.fs.iter().map(move |fs| (Backend::Marlin, *pc, *fs)))
+                    .collect(),
+                backend => vec![(*backend, PcScheme::default(), FsScheme::default())],
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Suite {
+    experiment: Vec<Experiment>,
+}
+
+struct Point {
+    experiment: String,
+    config: RunConfig,
+}
+
+impl Point {
+    // Marlin points only name the options that differ from the defaults, so
+    // they keep the names they had before there was a choice
+    fn path(&self, out_dir: &Path) -> PathBuf {
+        let config = &self.config;
+        let suffix = match config.backend {
+            Backend::Marlin => [
+                (config.pc != PcScheme::default()).then(|| config.pc.name()),
+                (config.fs != FsScheme::default()).then(|| config.fs.name()),
+            ]
+            .into_iter()
+            .flatten()
+            .map(|name| format!("-{}", name))
+            .collect(),
+            backend => format!("-{}", backend.name()),
+        };
+        let inputs = match config.public_inputs {
+            0 => String::new(),
+            k => format!("-inputs{}", k),
+        };
+        // A record measured with other options is not reused
+        let options: String = [
+            (config.seed != 0).then(|| format!("seed{}", config.seed)),
+            (config.warmup != 0).then(|| format!("warmup{}", config.warmup)),
+            (config.iterations != 1).then(|| format!("iterations{}", config.iterations)),
+            (config.threads != 0).then(|| format!("threads{}", config.threads)),
+            config.memory.then(|| "memory".to_string()),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|option| format!("-{}", option))
+        .collect();
+        out_dir.join(&self.experiment).join(format!(
+            "{}-{}-{}{}{}{}.json",
+            file_circuit::result_name(&self.config.circuit),
+            self.config.curve,
+            self.config.rounds,
+            inputs,
+            suffix,
+            options
+        ))
+    }
+}
+
+// Parses one term of a rounds spec: "N" or "2^N"
+fn parse_term(term: &str) -> Result<usize, String> {
+    let term = term.trim();
+    let value = match term.split_once('^') {
+        Some((base, exp)) => {
+            let base: usize = base.trim().parse().map_err(|_| format!("invalid base in {}", term))?;
+            let exp: u32 = exp.trim().parse().map_err(|_| format!("invalid exponent in {}", term))?;
+            base.checked_pow(exp).ok_or(format!("{} is too large", term))?
+        }
+        None => term.parse().map_err(|_| format!("invalid rounds {}", term))?,
+    };
+    Ok(value)
+}
+
+/// Expands a rounds spec into the list of rounds.
+///
+/// The spec is a comma separated list of values (`32`, `2^5`), linear ranges
+/// (`1..8`) and geometric ranges (`2^5..2^15`), all of them inclusive.
+pub fn parse_rounds(spec: &str) -> Result<Vec<usize>, String> {
+    let mut rounds = Vec::new();
+
+    for item in spec.split(',') {
+        match item.split_once("..") {
+            Some((start, end)) if start.contains('^') && end.contains('^') => {
+                let (base, start_exp) = start.trim().split_once('^').unwrap();
+                let (end_base, end_exp) = end.trim().split_once('^').unwrap();
+                if base.trim() != end_base.trim() {
+                    return Err(format!("range {} mixes different bases", item));
+                }
+                let start_exp: u32 = start_exp.trim().parse().map_err(|_| format!("invalid range {}", item))?;
+                let end_exp: u32 = end_exp.trim().parse().map_err(|_| format!("invalid range {}", item))?;
+                for exp in start_exp..=end_exp {
+                    rounds.push(parse_term(&format!("{}^{}", base, exp))?);
+                }
+            }
+            Some((start, end)) => {
+                rounds.extend(parse_term(start)?..=parse_term(end)?);
+            }
+            None => rounds.push(parse_term(item)?),
+        }
+    }
+
+    if rounds.contains(&0) {
+        return Err("0 is not a valid number of rounds".to_string());
+    }
+    Ok(rounds)
+}
+
+fn load_experiments(args: &SweepArgs) -> Vec<Experiment> {
+    match &args.suite {
+        Some(path) => {
+            let content = fs::read_to_string(path)
+                .unwrap_or_else(|e| print_panic!("Cannot read {}: {}", path.display(), e));
+            let suite: Suite = toml::from_str(&content)
+                .unwrap_or_else(|e| print_panic!("Invalid suite {}: {}", path.display(), e));
+            suite.experiment
+        }
+        None => vec![Experiment {
+            name: "sweep".to_string(),
+            circuits: args.circuits.clone(),
+            curves: args.curves.clone(),
+            rounds: args.rounds.clone(),
+            backends: args.backends.clone(),
+            pcs: args.pcs.clone(),
+            fs: args.fs.clone(),
+            public_inputs: args.public_inputs.clone(),
+        }],
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{}h{:02}m{:02}s", secs / 3600, (secs / 60) % 60, secs % 60)
+}
+
+// Flattens nested objects into dotted keys, keeping the field order
+fn flatten(prefix: &str, value: &Value, out: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten(&key, value, out);
+            }
+        }
+        Value::String(s) => out.push((prefix.to_string(), s.clone())),
+        Value::Null => out.push((prefix.to_string(), String::new())),
+        Value::Array(values) => out.push((
+            prefix.to_string(),
+            values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(";"),
+        )),
+        other => out.push((prefix.to_string(), other.to_string())),
+    }
+}
+
+// Field quoted as RFC 4180 asks when it holds a separator, a quote or a line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn write_csv(path: &Path, results: &[&RunResult]) {
+    let rows: Vec<Vec<(String, String)>> = results
+        .iter()
+        .map(|r| {
+            let mut row = Vec::new();
+            flatten("", &serde_json::to_value(r).unwrap(), &mut row);
+            row
+        })
+        .collect();
+
+    let Some(first) = rows.first() else { return };
+    let mut csv = first.iter().map(|(k, _)| csv_field(k)).collect::<Vec<_>>().join(",");
+    csv.push_str("\r\n");
+    for row in &rows {
+        csv.push_str(&row.iter().map(|(_, v)| csv_field(v)).collect::<Vec<_>>().join(","));
+        csv.push_str("\r\n");
+    }
+
+    fs::write(path, csv).unwrap_or_else(|e| print_panic!("Cannot write {}: {}", path.display(), e));
+}
+
+fn print_summary(results: &[(String, RunResult)], failed: &[String]) {
+    // Backends with a commitment scheme and a transcript have long names
+    let system = results.iter().map(|(_, r)| r.system().len()).max().unwrap_or(0).max("system".len());
+    println!(
+        "\n{:<12} {:<12} {:<10} {:<system$} {:>8} {:>12} {:>8} {:>8} {:>12} {:>12} {:>12} {:>10} {:>9}",
+        "experiment", "circuit", "curve", "system", "rounds", "constraints", "inputs", "|X|", "index/setup", "prover",
+        "verifier", "proof (B)", "verified"
+    );
+    for (experiment, r) in results {
+        // Groth16 has no indexer, its circuit-specific setup plays that role
+        let preprocessing = match r.backend {
+            Backend::Marlin => &r.indexer,
+            Backend::Groth16 => &r.setup,
+        };
+        println!(
+            "{:<12} {:<12} {:<10} {:<system$} {:>8} {:>12} {:>8} {:>8} {:>12} {:>12} {:>12} {:>10} {:>9}",
+            experiment,
+            file_circuit::short_name(&r.circuit),
+            r.curve,
+            r.system(),
+            r.rounds,
+            r.constraint_system.constraints,
+            // Public inputs of the circuit, without the constant one
+            r.constraint_system.instance_variables - 1,
+            r.constraint_system.domain_x.as_ref().map_or("-".to_string(), |domain| domain.size.to_string()),
+            format_ns(preprocessing.median_ns),
+            format_ns(r.prover.median_ns),
+            format_ns(r.verifier.median_ns),
+            r.sizes.proof.compressed,
+            r.verified
+        );
+    }
+    for point in failed {
+        println!("{:<12} {}", "FAILED", point);
+    }
+}
+
+// Result stored for a point, if it has one that can still be read
+fn load(path: &Path) -> Option<RunResult> {
+    if !path.exists() {
+        return None;
+    }
+    let content = fs::read_to_string(path).unwrap_or_else(|e| print_panic!("Cannot read {}: {}", path.display(), e));
+    match serde_json::from_str::<RunResult>(&content) {
+        Ok(result) if result.verified => Some(result),
+        // Rerun points whose proof did not verify, in case it was a fluke
+        Ok(_) => {
+            print_info!("Rerunning {}, its proof did not verify", path.display());
+            None
+        }
+        // Rerun points whose record is stale or truncated
+        Err(_) => {
+            print_info!("Discarding unreadable {}", path.display());
+            None
+        }
+    }
+}
+
+/// Runs every point of the requested experiments, skipping the ones that
+/// already have a verified result file in the output directory. Returns
+/// whether every point ran and verified.
+pub fn sweep(args: &SweepArgs, run: fn(&RunConfig) -> RunResult) -> bool {
+    let experiments = load_experiments(args);
+    let srs_dir = args.srs_dir.clone().unwrap_or_else(|| args.out_dir.join("srs"));
+
+    let mut points = Vec::new();
+    for experiment in &experiments {
+        let rounds = parse_rounds(&experiment.rounds)
+            .unwrap_or_else(|e| print_panic!("Experiment {}: {}", experiment.name, e));
+        for circuit in &experiment.circuits {
+            for curve in &experiment.curves {
+                for r in &rounds {
+                    for public_inputs in &experiment.public_inputs {
+                        for (backend, pc, fs) in experiment.systems() {
+                            // The inner product argument runs over the groups and the rest over the curves
+                            if (backend == Backend::Marlin && pc == PcScheme::Ipa) != registry::is_group(curve) {
+                                continue;
+                            }
+                            if backend == Backend::Marlin && !fs.supports(curve) {
+                                continue;
+                            }
+                            points.push(Point {
+                                experiment: experiment.name.clone(),
+                                config: RunConfig {
+                                    backend,
+                                    pc,
+                                    fs,
+                                    circuit: circuit.clone(),
+                                    curve: curve.clone(),
+                                    rounds: *r,
+                                    public_inputs: *public_inputs,
+                                    seed: args.seed,
+                                    prover_variant: 0,
+                                    warmup: args.warmup,
+                                    iterations: args.iterations,
+                                    threads: args.threads,
+                                    memory: args.memory,
+                soundness_check: false,
+                                    batch: 0,
+                                    srs_dir: Some(srs_dir.clone()),
+                                    srs_max_degree: args.srs_max_degree,
+                                },
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Every Marlin point of a curve and scheme shares a single SRS sized for the
+    // largest one, which the first point to run generates
+    let mut degrees: HashMap<String, usize> = HashMap::new();
+    let point_degrees: Vec<Option<usize>> = points
+        .iter()
+        .map(|point| {
+            let config = &point.config;
+            if config.backend != Backend::Marlin {
+                return None;
+            }
+            // The circuit may not exist or be broken, which its run reports
+            let degree = panic::catch_unwind(|| srs::required_degree_of(config)).ok().flatten()?;
+            let max = degrees.entry(config.pc.srs_name(&config.curve)).or_default();
+            *max = (*max).max(degree);
+            Some(degree)
+        })
+        .collect();
+    for (point, degree) in points.iter_mut().zip(point_degrees) {
+        if degree.is_some() {
+            let max = degrees[&point.config.pc.srs_name(&point.config.curve)];
+            point.config.srs_max_degree = point.config.srs_max_degree.max(max);
+        }
+    }
+
+    // Unreadable and unverified records are rerun, so they are pending like the missing ones
+    let mut done: Vec<Option<RunResult>> = points.iter().map(|p| load(&p.path(&args.out_dir))).collect();
+    let pending = done.iter().filter(|result| result.is_none()).count();
+    print_info!(
+        "Sweeping {} points, {} already done",
+        points.len(),
+        points.len() - pending
+    );
+
+    let mut results: Vec<(String, RunResult)> = Vec::new();
+    let mut failed = Vec::new();
+    let mut executed = 0;
+    let mut elapsed = Duration::ZERO;
+
+    for (i, point) in points.iter().enumerate() {
+        let path = point.path(&args.out_dir);
+        let label = format!(
+            "{} {} {} {} rounds: {}, extra public inputs: {}",
+            point.experiment,
+            point.config.circuit,
+            point.config.curve,
+            system_name(point.config.backend, (point.config.backend == Backend::Marlin).then_some((point.config.pc, point.config.fs))),
+            point.config.rounds,
+            point.config.public_inputs
+        );
+
+        if let Some(result) = done[i].take() {
+            print_info!("[{}/{}] {} (done)", i + 1, points.len(), label);
+            results.push((point.experiment.clone(), result));
+            continue;
+        }
+
+        let eta = match executed {
+            0 => "unknown".to_string(),
+            _ => format_duration(elapsed / executed as u32 * (pending - executed) as u32),
+        };
+        print_info!("[{}/{}] {} (ETA {})", i + 1, points.len(), label, eta);
+
+        let start = Instant::now();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| run(&point.config)));
+        elapsed += start.elapsed();
+        executed += 1;
+
+        match result {
+            Ok(result) => {
+                fs::create_dir_all(path.parent().unwrap())
+                    .unwrap_or_else(|e| print_panic!("Cannot create {}: {}", args.out_dir.display(), e));
+                fs::write(&path, serde_json::to_string_pretty(&result).unwrap())
+                    .unwrap_or_else(|e| print_panic!("Cannot write {}: {}", path.display(), e));
+                results.push((point.experiment.clone(), result));
+            }
+            Err(_) => failed.push(label),
+        }
+    }
+
+    for experiment in &experiments {
+        let experiment_results: Vec<&RunResult> = results
+            .iter()
+            .filter(|(name, _)| name == &experiment.name)
+            .map(|(_, r)| r)
+            .collect();
+        write_csv(
+            &args.out_dir.join(format!("{}.csv", experiment.name)),
+            &experiment_results,
+        );
+    }
+
+    print_summary(&results, &failed);
+    failed.is_empty() && results.iter().all(|(_, r)| r.verified)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{csv_field, parse_rounds, Point};
+    use crate::pc::PcScheme;
+    use crate::report::Backend;
+    use crate::transcript::FsScheme;
+    use crate::RunConfig;
+
+    fn point(circuit: &str) -> Point {
+        Point {
+            experiment: "sweep".to_string(),
+            config: RunConfig {
+                backend: Backend::Marlin,
+                pc: PcScheme::default(),
+                fs: FsScheme::default(),
+                circuit: circuit.to_string(),
+                curve: "bls12_381".to_string(),
+                rounds: 2,
+                public_inputs: 0,
+                seed: 0,
+                prover_variant: 0,
+                warmup: 0,
+                iterations: 1,
+                threads: 0,
+                memory: false,
+                soundness_check: false,
+                batch: 0,
+                srs_dir: None,
+                srs_max_degree: 0,
+            },
+        }
+    }
+
+    #[test]
+    fn geometric_range() {
+        assert_eq!(parse_rounds("2^5..2^15").unwrap(), (5..=15).map(|exp| 1 << exp).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn linear_range() {
+        assert_eq!(parse_rounds("1..8").unwrap(), vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn list_of_values_and_ranges() {
+        assert_eq!(parse_rounds("32, 2^3, 3^1..3^2, 1..2").unwrap(), vec![32, 8, 3, 9, 1, 2]);
+    }
+
+    #[test]
+    fn mixed_bases_are_rejected() {
+        assert!(parse_rounds("2^3..3^5").is_err());
+    }
+
+    #[test]
+    fn zero_is_rejected() {
+        assert!(parse_rounds("0").is_err());
+        assert!(parse_rounds("0..4").is_err());
+        assert!(parse_rounds("4,0").is_err());
+    }
+
+    #[test]
+    fn csv_fields_are_quoted() {
+        assert_eq!(csv_field("basic"), "basic");
+        assert_eq!(csv_field("circuits/a,b.r1cs"), "\"circuits/a,b.r1cs\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn circuit_files_are_told_apart_by_directory() {
+        let out_dir = Path::new("results");
+        let a = point("a/circuit.r1cs").path(out_dir);
+        let b = point("b/circuit.r1cs").path(out_dir);
+        assert_ne!(a, b);
+        assert!(a.file_name().unwrap().to_str().unwrap().starts_with("circuit-"));
+    }
+
+    #[test]
+    fn options_name_the_record() {
+        let out_dir = Path::new("results");
+        let default = point("basic");
+        assert_eq!(default.path(out_dir), out_dir.join("sweep/basic-bls12_381-2.json"));
+
+        let mut measured = point("basic");
+        measured.config.seed = 7;
+        measured.config.iterations = 5;
+        measured.config.threads = 4;
+        assert_eq!(
+            measured.path(out_dir),
+            out_dir.join("sweep/basic-bls12_381-2-seed7-iterations5-threads4.json")
+        );
+    }
+}
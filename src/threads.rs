@@ -0,0 +1,33 @@
+use crate::print_panic;
+
+/// Runs `f` in a pool of `threads` threads, or one per core with 0
+#[cfg(feature = "parallel")]
+pub fn install<T: Send>(threads: usize, f: impl FnOnce() -> T + Send) -> T {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .unwrap_or_else(|e| print_panic!("Cannot create the thread pool: {}", e))
+        .install(f)
+}
+
+/// Runs `f` in the only thread available without the parallel feature
+#[cfg(not(feature = "parallel"))]
+pub fn install<T: Send>(threads: usize, f: impl FnOnce() -> T + Send) -> T {
+    if threads > 1 {
+        print_panic!("{} threads requested, but the parallel feature is not enabled", threads)
+    }
+    f()
+}
+
+/// Threads used by the current pool
+pub fn current() -> usize {
+    #[cfg(feature = "parallel")]
+    return rayon::current_num_threads();
+    #[cfg(not(feature = "parallel"))]
+    return 1;
+}
+
+/// Threads of a pool created with `threads`, resolving 0 to one per core
+pub fn resolve(threads: usize) -> usize {
+    install(threads, current)
+}
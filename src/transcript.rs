@@ -0,0 +1,264 @@
+//! Fiat-Shamir transcripts that Marlin can derive its challenges from.
+//!
+//! `FiatShamirRng` of ark-marlin hashes the transcript with a byte digest and
+//! expands it with ChaCha. `PoseidonTranscript` instead chains the Poseidon
+//! hash over field elements and takes the challenges from its outputs. It
+//! still absorbs byte serializations and samples challenges from bytes, so it
+//! is not an algebraic sponge that a circuit could check cheaply, only a way
+//! to measure Poseidon as the hash of the transcript.
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use ark_ff::{BigInteger, FpParameters, PrimeField, ToBytes};
+use ark_marlin::rng::FiatShamirRng;
+use ark_std::rand::RngCore;
+use arkworks_native_gadgets::poseidon::sbox::PoseidonSbox;
+use arkworks_native_gadgets::poseidon::{FieldHasher, Poseidon, PoseidonParameters};
+use arkworks_utils::poseidon_params::setup_poseidon_params;
+use arkworks_utils::{bytes_matrix_to_f, bytes_vec_to_f, Curve};
+use blake2::{Blake2b, Blake2s};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sha3::Keccak256;
+
+use crate::print_panic;
+
+/// Transcript Marlin derives its challenges from
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[value(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum FsScheme {
+    /// Blake2s digest expanded with ChaCha, as upstream Marlin
+    #[default]
+    Blake2s,
+    /// Blake2b digest expanded with ChaCha
+    Blake2b,
+    /// SHA-256 digest expanded with ChaCha
+    Sha256,
+    /// Keccak-256 digest expanded with ChaCha
+    Keccak256,
+    /// Poseidon hash over the scalar field, only for bls12_381 and bn254
+    Poseidon,
+}
+
+/// Curves whose scalar field has Poseidon parameters
+pub const POSEIDON_CURVES: &[&str] = &["bls12_381", "bn254"];
+
+impl FsScheme {
+    /// Whether the transcript can run over the scalar field of `curve`
+    pub fn supports(&self, curve: &str) -> bool {
+        *self != FsScheme::Poseidon || POSEIDON_CURVES.contains(&curve)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FsScheme::Blake2s => "blake2s",
+            FsScheme::Blake2b => "blake2b",
+            FsScheme::Sha256 => "sha256",
+            FsScheme::Keccak256 => "keccak256",
+            FsScheme::Poseidon => "poseidon",
+        }
+    }
+}
+
+/// Source of the verifier challenges of Marlin
+pub trait Transcript: RngCore {
+    fn from_seed<T: ToBytes>(seed: &T) -> Self;
+    fn absorb<T: ToBytes>(&mut self, data: &T);
+}
+
+impl<D: digest::Digest> Transcript for FiatShamirRng<D> {
+    fn from_seed<T: ToBytes>(seed: &T) -> Self {
+        FiatShamirRng::from_seed(seed)
+    }
+
+    fn absorb<T: ToBytes>(&mut self, data: &T) {
+        FiatShamirRng::absorb(self, data)
+    }
+}
+
+pub type Blake2sTranscript = FiatShamirRng<Blake2s>;
+pub type Blake2bTranscript = FiatShamirRng<Blake2b>;
+pub type Sha256Transcript = FiatShamirRng<Sha256>;
+pub type Keccak256Transcript = FiatShamirRng<Keccak256>;
+
+// Poseidon parameters with x^5 and width 3 for the fields that have them
+fn parse_poseidon_params<F: PrimeField>() -> PoseidonParameters<F> {
+    let curve = if TypeId::of::<F>() == TypeId::of::<ark_bls12_381::Fr>() {
+        Curve::Bls381
+    } else if TypeId::of::<F>() == TypeId::of::<ark_bn254::Fr>() {
+        Curve::Bn254
+    } else {
+        print_panic!("There are no Poseidon parameters for this field, only for bls12_381 and bn254")
+    };
+    let data = setup_poseidon_params(curve, 5, 3).unwrap();
+    PoseidonParameters::new(
+        bytes_vec_to_f(&data.rounds),
+        bytes_matrix_to_f(&data.mds),
+        data.full_rounds,
+        data.partial_rounds,
+        data.width,
+        PoseidonSbox(data.exp),
+    )
+}
+
+// Parameters are parsed once per field, so that the timed transcripts only
+// pay for hashing
+fn poseidon_params<F: PrimeField>() -> PoseidonParameters<F> {
+    static PARAMS: OnceLock<Mutex<HashMap<TypeId, Box<dyn Any + Send + Sync>>>> = OnceLock::new();
+    let mut params = PARAMS.get_or_init(Default::default).lock().unwrap();
+    params
+        .entry(TypeId::of::<F>())
+        .or_insert_with(|| Box::new(parse_poseidon_params::<F>()))
+        .downcast_ref::<PoseidonParameters<F>>()
+        .unwrap()
+        .clone()
+}
+
+/// Transcript that chains the Poseidon hash over the field `F`.
+///
+/// Absorbing hashes every element into the state, `state = H(state, x)`, and
+/// each challenge is `state = H(state, -1)`. The bytes of absorbed values are
+/// packed into elements smaller than the modulus, so they never equal -1.
+pub struct PoseidonTranscript<F: PrimeField> {
+    hasher: Poseidon<F>,
+    state: F,
+    // Bytes of the last challenge not returned yet
+    buffer: Vec<u8>,
+}
+
+impl<F: PrimeField> PoseidonTranscript<F> {
+    // Whole bytes that fit below the modulus
+    const BYTES_PER_ELEMENT: usize = (F::Params::MODULUS_BITS as usize - 1) / 8;
+
+    fn hash(&mut self, x: F) {
+        self.state = self.hasher.hash_two(&self.state, &x).unwrap();
+    }
+
+    fn absorb_bytes(&mut self, bytes: &[u8]) {
+        // The length keeps values that only differ in trailing zeros apart
+        self.hash(F::from(bytes.len() as u64));
+        for chunk in bytes.chunks(Self::BYTES_PER_ELEMENT) {
+            self.hash(F::from_le_bytes_mod_order(chunk));
+        }
+        self.buffer.clear();
+    }
+
+    fn squeeze(&mut self) {
+        self.hash(-F::one());
+        let bytes = self.state.into_repr().to_bytes_le();
+        self.buffer.extend(bytes[..Self::BYTES_PER_ELEMENT].iter().rev());
+    }
+}
+
+impl<F: PrimeField> Transcript for PoseidonTranscript<F> {
+    fn from_seed<T: ToBytes>(seed: &T) -> Self {
+        let mut transcript = PoseidonTranscript {
+            hasher: Poseidon::new(poseidon_params()),
+            state: F::zero(),
+            buffer: Vec::new(),
+        };
+        transcript.absorb(seed);
+        transcript
+    }
+
+    fn absorb<T: ToBytes>(&mut self, data: &T) {
+        let mut bytes = Vec::new();
+        data.write(&mut bytes).expect("failed to convert to bytes");
+        self.absorb_bytes(&bytes);
+    }
+}
+
+impl<F: PrimeField> RngCore for PoseidonTranscript<F> {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for byte in dest {
+            if self.buffer.is_empty() {
+                self.squeeze();
+            }
+            *byte = self.buffer.pop().unwrap();
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), ark_std::rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::PairingEngine;
+    use ark_ff::PrimeField;
+    use ark_std::rand::RngCore;
+
+    use super::{PoseidonTranscript, Transcript};
+    use crate::bench::{self, PC};
+    use crate::instrumented::{InstrumentedMarlin, VerifierTimings};
+    use crate::seed::{self, Stream};
+
+    // Whether an honest proof verifies, and a proof of other public inputs does not
+    fn round_trip<E: PairingEngine>() {
+        let (vk, mut instances) = bench::proved::<E::Fr, PC<E>, PoseidonTranscript<E::Fr>>(1);
+        let (mut inputs, proof) = instances.pop().unwrap();
+
+        let verify = |inputs: &[E::Fr]| {
+            let rng = &mut seed::rng(0, Stream::Verifier);
+            InstrumentedMarlin::<E::Fr, PC<E>, PoseidonTranscript<E::Fr>>::verify(
+                &vk,
+                inputs,
+                &proof,
+                rng,
+                &mut VerifierTimings::default(),
+            )
+            .unwrap()
+        };
+        assert!(verify(&inputs));
+        inputs[0] += E::Fr::from(1u64);
+        assert!(!verify(&inputs));
+    }
+
+    #[test]
+    fn round_trip_bls12_381() {
+        round_trip::<ark_bls12_381::Bls12_381>();
+    }
+
+    #[test]
+    fn round_trip_bn254() {
+        round_trip::<ark_bn254::Bn254>();
+    }
+
+    // Field elements and bytes squeezed after absorbing `data`
+    fn squeezed<F: PrimeField>(data: &[u64]) -> (Vec<F>, Vec<u8>) {
+        let mut transcript = PoseidonTranscript::<F>::from_seed(&b"seed".to_vec());
+        for x in data {
+            transcript.absorb(x);
+        }
+        let elements = (0..4).map(|_| F::rand(&mut transcript)).collect();
+        let mut bytes = vec![0u8; 100];
+        transcript.fill_bytes(&mut bytes);
+        (elements, bytes)
+    }
+
+    #[test]
+    fn deterministic() {
+        type F = ark_bls12_381::Fr;
+        assert_eq!(squeezed::<F>(&[1, 2, 3]), squeezed::<F>(&[1, 2, 3]));
+        assert_ne!(squeezed::<F>(&[1, 2, 3]), squeezed::<F>(&[1, 2, 4]));
+        assert_ne!(squeezed::<F>(&[1, 2, 3]).1, squeezed::<F>(&[1, 2, 3, 0]).1);
+    }
+}
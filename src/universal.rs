@@ -0,0 +1,245 @@
+use std::time::{Duration, Instant};
+
+use ark_ec::PairingEngine;
+use ark_marlin::UniversalSRS;
+use ark_poly_commit::{PCUniversalParams, PolynomialCommitment};
+use clap::Args as ClapArgs;
+use serde::Serialize;
+
+use crate::bench::{synthesize, PC};
+use crate::circuit_traits::BenchCircuit;
+use crate::instrumented::{IndexerTimings, InstrumentedMarlin, ProverTimings, VerifierTimings};
+use crate::registry::{self, CircuitVisitor, CurveVisitor, CIRCUITS};
+use crate::report::OutputFormat;
+use crate::seed::{self, Stream};
+use crate::sizes::Size;
+use crate::stats::format_ns;
+use crate::transcript::Blake2sTranscript;
+use crate::{print_info, print_panic, srs};
+
+type InstrumentedMarlinInst<E> = InstrumentedMarlin<<E as PairingEngine>::Fr, PC<E>, Blake2sTranscript>;
+
+#[derive(ClapArgs, Debug)]
+pub struct UniversalArgs {
+    /// Circuits indexed from the same SRS, all of them by default
+    #[arg(short = 's', long, value_delimiter = ',')]
+    circuits: Vec<String>,
+
+    /// Number of rounds of every circuit
+    #[arg(short, long, default_value_t = 1)]
+    rounds: usize,
+
+    /// Curve, see --list-curves
+    #[arg(short, long, default_value = "bls12_381")]
+    curve: String,
+
+    /// Seed of the RNG used for the circuits, the SRS and the proofs
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+}
+
+/// One circuit indexed from the shared SRS and from an SRS of its own size
+#[derive(Debug, Serialize)]
+pub struct UniversalCircuit {
+    pub circuit: String,
+    pub max_degree: usize,
+    // Setup, index and trim with an SRS of exactly the degree the circuit needs
+    pub exact_setup_ns: u64,
+    pub exact_index_ns: u64,
+    pub exact_trim_ns: u64,
+    // Index and trim with the shared SRS
+    pub index_ns: u64,
+    pub trim_ns: u64,
+    pub proving_key: Size,
+    pub verifying_key: Size,
+    pub prover_ns: u64,
+    pub verified: bool,
+}
+
+/// Every circuit indexed from a single SRS
+#[derive(Debug, Serialize)]
+pub struct UniversalRun {
+    pub curve: String,
+    pub rounds: usize,
+    pub seed: u64,
+    pub srs_max_degree: usize,
+    pub setup_ns: u64,
+    pub srs: Size,
+    pub circuits: Vec<UniversalCircuit>,
+}
+
+fn nanos(duration: Duration) -> u64 {
+    duration.as_nanos() as u64
+}
+
+// Degree of the SRS needed by each visited circuit
+struct RequiredDegree<'a>(&'a UniversalArgs);
+
+impl<F: ark_ff::PrimeField> CircuitVisitor<F> for RequiredDegree<'_> {
+    type Output = usize;
+
+    fn visit<C: BenchCircuit<F>>(self, params: C::Params) -> Self::Output {
+        let c = C::new_random(&params, &mut seed::rng(self.0.seed, Stream::Circuit), self.0.rounds);
+        srs::required_degree(&synthesize(&c).0)
+    }
+}
+
+// Indexes, proves and verifies each visited circuit with the shared SRS
+struct IndexWithSrs<'a, E: PairingEngine>(&'a UniversalArgs, &'a UniversalSRS<E::Fr, PC<E>>, &'a str);
+
+impl<E: PairingEngine> CircuitVisitor<E::Fr> for IndexWithSrs<'_, E> {
+    type Output = UniversalCircuit;
+
+    fn visit<C: BenchCircuit<E::Fr>>(self, params: C::Params) -> Self::Output {
+        let IndexWithSrs(args, shared_srs, name) = self;
+        let c = C::new_random(&params, &mut seed::rng(args.seed, Stream::Circuit), args.rounds);
+        let max_degree = srs::required_degree(&synthesize(&c).0);
+
+        // Baseline with an SRS of the exact size
+        let start = Instant::now();
+        let exact_srs = PC::<E>::setup(max_degree, None, &mut seed::rng(args.seed, Stream::Setup)).unwrap();
+        let exact_setup_ns = nanos(start.elapsed());
+        let mut exact_timings = IndexerTimings::default();
+        let start = Instant::now();
+        InstrumentedMarlinInst::<E>::index(&exact_srs, c.clone(), &mut exact_timings).unwrap();
+        let exact_index_ns = nanos(start.elapsed());
+        drop(exact_srs);
+
+        let mut timings = IndexerTimings::default();
+        let start = Instant::now();
+        let (pk, vk) = InstrumentedMarlinInst::<E>::index(shared_srs, c.clone(), &mut timings).unwrap();
+        let index_ns = nanos(start.elapsed());
+
+        let start = Instant::now();
+        let proof = InstrumentedMarlinInst::<E>::prove(
+            &pk,
+            c.clone(),
+            &mut seed::prover_rng(args.seed, 0),
+            &mut ProverTimings::default(),
+        )
+        .unwrap();
+        let prover_ns = nanos(start.elapsed());
+
+        let verified = InstrumentedMarlinInst::<E>::verify(
+            &vk,
+            &c.public_inputs(),
+            &proof,
+            &mut seed::rng(args.seed, Stream::Verifier),
+            &mut VerifierTimings::default(),
+        )
+        .unwrap();
+
+        UniversalCircuit {
+            circuit: name.to_string(),
+            max_degree,
+            exact_setup_ns,
+            exact_index_ns,
+            exact_trim_ns: nanos(exact_timings.trim),
+            index_ns,
+            trim_ns: nanos(timings.trim),
+            proving_key: Size::of(&pk),
+            verifying_key: Size::of(&vk),
+            prover_ns,
+            verified,
+        }
+    }
+}
+
+struct UniversalOnCurve<'a>(&'a UniversalArgs, &'a [String]);
+
+impl CurveVisitor for UniversalOnCurve<'_> {
+    type Output = UniversalRun;
+
+    fn visit<E: PairingEngine>(self) -> Self::Output {
+        let UniversalOnCurve(args, circuits) = self;
+
+        // One SRS for the largest circuit
+        let srs_max_degree = circuits
+            .iter()
+            .map(|name| {
+                registry::visit_circuit::<E::Fr, _>(name, RequiredDegree(args))
+                    .unwrap_or_else(|| print_panic!("Invalid circuit {}", name))
+            })
+            .max()
+            .unwrap();
+        let start = Instant::now();
+        let shared_srs = PC::<E>::setup(srs_max_degree, None, &mut seed::rng(args.seed, Stream::Setup))
+            .unwrap_or_else(|e| print_panic!("Setup failed: {:?}", e));
+        let setup_ns = nanos(start.elapsed());
+
+        let circuits = circuits
+            .iter()
+            .map(|name| registry::visit_circuit(name, IndexWithSrs::<E>(args, &shared_srs, name)).unwrap())
+            .collect();
+
+        UniversalRun {
+            curve: args.curve.clone(),
+            rounds: args.rounds,
+            seed: args.seed,
+            srs_max_degree: shared_srs.max_degree(),
+            setup_ns,
+            srs: Size::of(&shared_srs),
+            circuits,
+        }
+    }
+}
+
+/// Indexes every selected circuit from one SRS sized for the largest of them
+pub fn universal(args: &UniversalArgs) -> UniversalRun {
+    if args.rounds == 0 {
+        print_panic!("0 is not a valid number of rounds")
+    }
+    let circuits: Vec<String> = if args.circuits.is_empty() {
+        CIRCUITS.iter().map(|entry| entry.name.to_string()).collect()
+    } else {
+        args.circuits.clone()
+    };
+
+    registry::visit_curve(&args.curve, UniversalOnCurve(args, &circuits))
+        .unwrap_or_else(|| print_panic!("Invalid curve {}", args.curve))
+}
+
+impl UniversalRun {
+    pub fn print(&self, format: OutputFormat) {
+        match format {
+            OutputFormat::Text => self.print_text(),
+            OutputFormat::Json => println!("{}", serde_json::to_string(self).unwrap()),
+        }
+    }
+
+    fn print_text(&self) {
+        print_info!(
+            "Universal SRS for {}, rounds: {}, seed: {}, max degree: {}",
+            self.curve,
+            self.rounds,
+            self.seed,
+            self.srs_max_degree
+        );
+        print_info!("Setup time: {}", format_ns(self.setup_ns as f64));
+        print_info!("SRS size: {} bytes ({} uncompressed)", self.srs.compressed, self.srs.uncompressed);
+        print_info!(
+            "Setup time of an exact SRS per circuit, added up: {}",
+            format_ns(self.circuits.iter().map(|c| c.exact_setup_ns).sum::<u64>() as f64)
+        );
+
+        println!(
+            "\n{:<12} {:>8} {:>12} {:>12} {:>12} {:>12} {:>12} {:>10} {:>10} {:>9}",
+            "circuit", "degree", "index", "exact index", "trim", "exact trim", "prover", "pk (B)", "vk (B)", "verified"
+        );
+        for c in &self.circuits {
+            println!(
+                "{:<12} {:>8} {:>12} {:>12} {:>12} {:>12} {:>12} {:>10} {:>10} {:>9}",
+                c.circuit,
+                c.max_degree,
+                format_ns(c.index_ns as f64),
+                format_ns(c.exact_index_ns as f64),
+                format_ns(c.trim_ns as f64),
+                format_ns(c.exact_trim_ns as f64),
+                format_ns(c.prover_ns as f64),
+                c.proving_key.compressed,
+                c.verifying_key.compressed,
+                c.verified
+            );
+        }
+    }
+}